@@ -0,0 +1,113 @@
+use std::sync::{Arc, RwLock};
+
+use criterion::{criterion_group, criterion_main, Criterion, BatchSize, black_box};
+use sandbox2::{elements::Element, field::{Field, chunk::{Chunk, NEVER_UPDATED, chunk_size}, chunk_context::{ChunkContext, ChunkNeighbourhood, NeighbourChunk, neighbourhood_index}, gravity::GravityMap, wind::WindMap, neighbours::Neighbours, rect::Rect}, scenes};
+
+const CHUNKS: (usize, usize) = (4, 4);
+const THREADS: usize = 4;
+const TICKS: usize = 50;
+
+fn world_size() -> (isize, isize){
+    let size = chunk_size();
+    ((CHUNKS.0 * size.0) as isize, (CHUNKS.1 * size.1) as isize)
+}
+
+fn scene(c: &mut Criterion, name: &str){
+    c.bench_function(&format!("{} {} ticks", name, TICKS), |b| b.iter_batched(
+        || {
+            let mut field = Field::new(CHUNKS, THREADS).unwrap();
+            scenes::load(name, &mut field, world_size()).unwrap();
+            field
+        },
+        |mut field| {
+            for _ in 0..TICKS{
+                field.update();
+            }
+            field
+        },
+        BatchSize::PerIteration));
+}
+
+fn scenes_bench(c: &mut Criterion){
+    for name in ["sand-collapse", "water-basin", "oil-water", "acid-block", "scattered"]{
+        scene(c, name);
+    }
+}
+
+fn filled_chunk() -> Chunk{
+    let size = chunk_size();
+    let mut chunk = Chunk::new();
+    for y in size.1 / 2..size.1{
+        for x in 0..size.0{
+            chunk.set((x, y), Element::sand(), NEVER_UPDATED);
+        }
+    }
+    chunk
+}
+
+fn neighbourhood() -> ChunkNeighbourhood{
+    let mut neighbours: ChunkNeighbourhood = Default::default();
+    for cord in Neighbours::of((1, 1)){
+        neighbours[neighbourhood_index((1, 1), cord).unwrap()] = NeighbourChunk::Chunk(Arc::new(RwLock::new(filled_chunk())));
+    }
+    neighbours
+}
+
+fn chunk_context_bench(c: &mut Criterion){
+    let size = chunk_size();
+    let area = Rect::from((size.0 as isize - 1, size.1 as isize - 1), (size.0 as isize * 2 + 1, size.1 as isize * 2 + 1));
+
+    let mut chunk = filled_chunk();
+    let neighbours = neighbourhood();
+    let gravity = GravityMap::default();
+    let wind = WindMap::default();
+    let ctx = ChunkContext::new(&mut chunk, (1, 1), &neighbours, &gravity, &wind, 1);
+    c.bench_function("chunk context get", |b| b.iter(|| {
+        let mut count = 0;
+        for position in area.into_iter(){
+            if let Ok(Some(_)) = ctx.get(position){
+                count += 1;
+            }
+        }
+        black_box(count)
+    }));
+
+    c.bench_function("chunk context set", |b| b.iter_batched(
+        || (filled_chunk(), neighbourhood()),
+        |(mut chunk, neighbours)| {
+            let mut ctx = ChunkContext::new(&mut chunk, (1, 1), &neighbours, &gravity, &wind, 1);
+            for position in area.into_iter(){
+                ctx.set(position, Element::water());
+            }
+            ctx.updated_coordinates.len()
+        },
+        BatchSize::SmallInput));
+}
+
+fn neighbours_bench(c: &mut Criterion){
+    let boundaries = Rect::from((0, 0), (64, 64));
+    c.bench_function("neighbours", |b| b.iter(|| {
+        let mut sum = 0;
+        for position in boundaries.into_iter(){
+            for (x, y) in Neighbours::of(position).with_boundaries(boundaries){
+                sum += x + y;
+            }
+        }
+        black_box(sum)
+    }));
+}
+
+fn rect_bench(c: &mut Criterion){
+    let rect = Rect::from((0, 0), (256, 256));
+    c.bench_function("rect iteration", |b| b.iter(|| {
+        black_box(rect).into_iter().fold(0, |sum, (x, y)| sum + x + y)
+    }));
+}
+
+criterion_group!{
+    name = simulation;
+    config = Criterion::default().sample_size(10);
+    targets = scenes_bench
+}
+criterion_group!(primitives, chunk_context_bench, neighbours_bench, rect_bench);
+criterion_main!(simulation, primitives);
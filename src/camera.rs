@@ -0,0 +1,112 @@
+use crate::field::rect::Rect;
+
+const MIN_ZOOM: f32 = 1.;
+const MAX_ZOOM: f32 = 16.;
+const ZOOM_STEP: f32 = 1.25;
+
+pub struct Camera{
+    offset: (f32, f32),
+    zoom: f32,
+    view_size: (u32, u32),
+    world_size: (u32, u32),
+}
+
+impl Camera {
+    pub fn new(view_size: (u32, u32), world_size: (u32, u32)) -> Camera{
+        let mut camera = Camera { offset: (0., 0.), zoom: MIN_ZOOM, view_size, world_size };
+        camera.clamp();
+        camera
+    }
+
+    fn visible_size(&self) -> (f32, f32){
+        (self.view_size.0 as f32 / self.zoom, self.view_size.1 as f32 / self.zoom)
+    }
+
+    fn clamp(&mut self){
+        let visible = self.visible_size();
+        let clamp_axis = |offset: f32, visible: f32, world: u32| {
+            if visible >= world as f32 {
+                (world as f32 - visible) / 2.
+            }
+            else {
+                offset.clamp(0., world as f32 - visible)
+            }
+        };
+        self.offset = (clamp_axis(self.offset.0, visible.0, self.world_size.0),
+            clamp_axis(self.offset.1, visible.1, self.world_size.1));
+    }
+
+    pub fn view_size(&self) -> (u32, u32){
+        self.view_size
+    }
+
+    pub fn resize(&mut self, view_size: (u32, u32)){
+        self.view_size = view_size;
+        self.clamp();
+    }
+
+    // delta is in view pixels
+    pub fn pan(&mut self, delta: (f32, f32)){
+        self.offset = (self.offset.0 - delta.0 / self.zoom, self.offset.1 - delta.1 / self.zoom);
+        self.clamp();
+    }
+
+    pub fn zoom_at(&mut self, steps: isize, view_point: (usize, usize)){
+        let anchor = (self.offset.0 + view_point.0 as f32 / self.zoom, self.offset.1 + view_point.1 as f32 / self.zoom);
+        self.zoom = (self.zoom * ZOOM_STEP.powi(steps as i32)).clamp(MIN_ZOOM, MAX_ZOOM);
+        self.offset = (anchor.0 - view_point.0 as f32 / self.zoom, anchor.1 - view_point.1 as f32 / self.zoom);
+        self.clamp();
+    }
+
+    pub fn view_to_world(&self, view_point: (usize, usize)) -> Option<(usize, usize)>{
+        let x = (self.offset.0 + (view_point.0 as f32 + 0.5) / self.zoom).floor();
+        let y = (self.offset.1 + (view_point.1 as f32 + 0.5) / self.zoom).floor();
+        if x < 0. || y < 0. || x >= self.world_size.0 as f32 || y >= self.world_size.1 as f32 {
+            return None;
+        }
+        Some((x as usize, y as usize))
+    }
+
+    pub fn world_to_view(&self, world_point: (isize, isize)) -> (isize, isize){
+        (((world_point.0 as f32 - self.offset.0) * self.zoom).floor() as isize,
+        ((world_point.1 as f32 - self.offset.1) * self.zoom).floor() as isize)
+    }
+
+    pub fn visible_rect(&self) -> Rect<isize>{
+        let visible = self.visible_size();
+        let left = self.offset.0.floor().max(0.) as isize;
+        let top = self.offset.1.floor().max(0.) as isize;
+        let right = (self.offset.0 + visible.0).ceil().min(self.world_size.0 as f32) as isize;
+        let bottom = (self.offset.1 + visible.1).ceil().min(self.world_size.1 as f32) as isize;
+        Rect::from((left, top), (right, bottom))
+    }
+
+    pub fn rect_to_view(&self, rect: Rect<isize>) -> Rect<isize>{
+        Rect::from(self.world_to_view((rect.left(), rect.top())), self.world_to_view((rect.right(), rect.bottom())))
+    }
+
+    pub fn blit(&self, world: &[u8], view: &mut [u8]){
+        let columns: Vec<Option<usize>> = (0..self.view_size.0)
+            .map(|x| {
+                let world_x = (self.offset.0 + (x as f32 + 0.5) / self.zoom).floor();
+                if world_x < 0. || world_x >= self.world_size.0 as f32 {None} else {Some(world_x as usize)}
+            })
+            .collect();
+        for (y, row) in view.chunks_exact_mut(self.view_size.0 as usize * 4).enumerate(){
+            let world_y = (self.offset.1 + (y as f32 + 0.5) / self.zoom).floor();
+            let world_row = if world_y < 0. || world_y >= self.world_size.1 as f32 {
+                None
+            }
+            else {
+                let start = world_y as usize * self.world_size.0 as usize * 4;
+                Some(&world[start..start + self.world_size.0 as usize * 4])
+            };
+            for (pixel, column) in row.chunks_exact_mut(4).zip(columns.iter()){
+                match (world_row, column) {
+                    (Some(world_row), Some(x)) => pixel.copy_from_slice(&world_row[x * 4..(x + 1) * 4]),
+                    _ => pixel.copy_from_slice(&[0x00, 0x00, 0x00, 0xff]),
+                }
+            }
+        }
+    }
+}
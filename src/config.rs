@@ -0,0 +1,144 @@
+use std::fs;
+
+use crate::field::{chunk::DEFAULT_CHUNK_SIZE, edges::{EdgeMode, WorldEdges}, gravity::Gravity};
+
+pub struct Config{
+    pub chunk_number: (usize, usize),
+    pub chunk_size: (usize, usize),
+    pub scale_factor: u32,
+    pub thread_number: usize, // zero picks one thread per core
+    pub fps: f32,
+    pub headless: Option<usize>,
+    pub scene: String,
+    pub metrics: Option<String>,
+    pub edges: WorldEdges,
+    pub gravity: Gravity,
+    pub repose: Option<String>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config { chunk_number: (8, 8), chunk_size: DEFAULT_CHUNK_SIZE, scale_factor: 6, thread_number: 0, fps: 300.,
+            headless: None, scene: "empty".to_string(), metrics: None, edges: WorldEdges::default(),
+            gravity: Gravity::default(), repose: None }
+    }
+}
+
+fn parse_pair(value: &str) -> Result<(usize, usize), String>{
+    let (x, y) = value.split_once('x').ok_or(format!("expected WIDTHxHEIGHT, got '{}'", value))?;
+    let x = x.trim().parse().map_err(|_| format!("invalid number '{}'", x))?;
+    let y = y.trim().parse().map_err(|_| format!("invalid number '{}'", y))?;
+    if x == 0 || y == 0{
+        return Err(format!("'{}' must not be zero", value));
+    }
+    Ok((x, y))
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, String>{
+    value.trim().parse().map_err(|_| format!("invalid number '{}'", value))
+}
+
+impl Config {
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String>{
+        match key {
+            "chunks" => self.chunk_number = parse_pair(value)?,
+            "chunk-size" => self.chunk_size = parse_pair(value)?,
+            "scale" => self.scale_factor = parse_number(value)?,
+            "threads" => self.thread_number = parse_number(value)?,
+            "fps" => self.fps = parse_number(value)?,
+            "headless" => self.headless = Some(parse_number(value)?),
+            "scene" => self.scene = value.to_string(),
+            "metrics" => self.metrics = Some(value.to_string()),
+            "edges" => self.edges = WorldEdges::all(EdgeMode::parse(value)?),
+            "edge-left" => self.edges.left = EdgeMode::parse(value)?,
+            "edge-right" => self.edges.right = EdgeMode::parse(value)?,
+            "edge-top" => self.edges.top = EdgeMode::parse(value)?,
+            "edge-bottom" => self.edges.bottom = EdgeMode::parse(value)?,
+            "gravity" => self.gravity = Gravity::parse(value)?,
+            "repose" => self.repose = Some(value.to_string()),
+            _ => return Err(format!("unknown option '{}'", key)),
+        }
+        Ok(())
+    }
+
+    fn load_file(&mut self, path: &str) -> Result<(), String>{
+        let content = fs::read_to_string(path).map_err(|e| format!("can't read '{}': {}", path, e))?;
+        for line in content.lines(){
+            let line = line.split('#').next().unwrap().trim();
+            if line.is_empty(){
+                continue;
+            }
+            let (key, value) = line.split_once('=').ok_or(format!("expected 'key = value', got '{}'", line))?;
+            self.set(key.trim(), value.trim())?;
+        }
+        Ok(())
+    }
+
+    // `--config` is applied first so the rest of the command line overrides it
+    pub fn from_args(args: impl Iterator<Item = String>) -> Result<Config, String>{
+        let args: Vec<String> = args.collect();
+        let mut options = Vec::new();
+        let mut config = Config::default();
+        let mut i = 0;
+        while i < args.len(){
+            let key = args[i].strip_prefix("--").ok_or(format!("unexpected argument '{}'", args[i]))?;
+            let value = args.get(i + 1).ok_or(format!("missing value for '--{}'", key))?;
+            if key == "config"{
+                config.load_file(value)?;
+            }
+            else{
+                options.push((key, value));
+            }
+            i += 2;
+        }
+        for (key, value) in options{
+            config.set(key, value)?;
+        }
+        if config.scale_factor == 0 || config.fps <= 0.{
+            return Err("scale and fps must be positive".to_string());
+        }
+        config.edges.check(config.chunk_number)?;
+        Ok(config)
+    }
+
+    pub fn world_size(&self) -> (u32, u32){
+        ((self.chunk_number.0 * self.chunk_size.0) as u32, (self.chunk_number.1 * self.chunk_size.1) as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(line: &str) -> impl Iterator<Item = String> + '_{
+        line.split_whitespace().map(str::to_string)
+    }
+
+    #[test]
+    fn pairs_are_parsed(){
+        assert_eq!(parse_pair("16x8"), Ok((16, 8)));
+        assert_eq!(parse_pair(" 4 x 2 "), Ok((4, 2)));
+    }
+
+    #[test]
+    fn zero_pairs_are_rejected(){
+        assert!(parse_pair("0x8").is_err());
+        assert!(parse_pair("8x0").is_err());
+    }
+
+    #[test]
+    fn malformed_pairs_are_rejected(){
+        for value in ["16", "16x", "x8", "16*8", "ax8", "-1x8", "16x8x2"]{
+            assert!(parse_pair(value).is_err(), "{} was accepted", value);
+        }
+    }
+
+    #[test]
+    fn bad_options_are_rejected(){
+        assert!(Config::from_args(args("--chunks 0x4")).is_err());
+        assert!(Config::from_args(args("--chunks 4x4 --scale 0")).is_err());
+        assert!(Config::from_args(args("--unknown 1")).is_err());
+        assert!(Config::from_args(args("--threads")).is_err());
+        assert!(Config::from_args(args("chunks 4x4")).is_err());
+    }
+}
@@ -0,0 +1,55 @@
+use crate::field::rect::Rect;
+
+pub fn convert_cords(cord: (isize, isize), size: (u32, u32)) -> Option<usize>{
+    if cord.0 < 0 || cord.1 < 0 || cord.0 >= size.0 as isize || cord.1 >= size.1 as isize{
+        return None;
+    }
+    let cord = (cord.0 as usize, cord.1 as usize);
+    Some(cord.1 * size.0 as usize + cord.0)
+}
+
+pub fn set_pix(frame: &mut [u8], size: (u32, u32), pix: (isize, isize), color: &[u8; 4]){
+    let index = convert_cords(pix, size);
+    if index.is_none(){
+        return;
+    }
+    let index = index.unwrap();
+    let pixel_buf = &mut frame[index*4..(index+1)*4];
+
+    pixel_buf[0] = color[0];
+    pixel_buf[1] = color[1];
+    pixel_buf[2] = color[2];
+    pixel_buf[3] = color[3];
+}
+
+pub fn draw_rect(frame: &mut [u8], size: (u32, u32), rect: Rect<isize>, color: &[u8; 4]){
+    for x in rect.left()..rect.right(){
+        set_pix(frame, size, (x, rect.top()), color);
+        set_pix(frame, size, (x, rect.bottom()-1), color);
+    }
+    for y in rect.top()..rect.bottom(){
+        set_pix(frame, size, (rect.left(), y), color);
+        set_pix(frame, size, (rect.right() - 1, y), color);
+    }
+}
+
+pub fn fill_rect(frame: &mut [u8], size: (u32, u32), rect: Rect<isize>, color: &[u8; 4]){
+    for y in rect.top()..rect.bottom(){
+        for x in rect.left()..rect.right(){
+            set_pix(frame, size, (x, y), color);
+        }
+    }
+}
+
+pub fn blend_rect(frame: &mut [u8], size: (u32, u32), rect: Rect<isize>, color: &[u8; 4], alpha: f32){
+    for y in rect.top()..rect.bottom(){
+        for x in rect.left()..rect.right(){
+            if let Some(index) = convert_cords((x, y), size){
+                let pixel_buf = &mut frame[index*4..(index+1)*4];
+                for i in 0..3{
+                    pixel_buf[i] = (pixel_buf[i] as f32 * (1. - alpha) + color[i] as f32 * alpha) as u8;
+                }
+            }
+        }
+    }
+}
@@ -1,12 +1,13 @@
-use std::iter;
+use std::{iter, cmp};
 
 use rand::{thread_rng, Rng};
 
-use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours, gravity::step};
 
-use super::{liquid::Liquid, Element, get_avalible_point, ElementType};
+use super::{liquid::Liquid, Element, get_avalible_point, ElementType, heat::heat_neighbours, material::MaterialProperties};
 
 const DESOLVE_CHANCE: f64 = 0.07;
+const MASS_PER_STRENGTH: f64 = 20.;
 
 fn move_and_clear(from: (isize, isize), to: (isize, isize), data: Liquid, strength: isize, field_access: &mut ChunkContext){
     field_access.clear(from);
@@ -16,80 +17,113 @@ fn move_and_clear(from: (isize, isize), to: (isize, isize), data: Liquid, streng
     }
 }
 
-fn clear_neighbours(of: (isize, isize), field_access: &mut ChunkContext, max_cleared: isize) -> isize {
-    let mut removed = 0;
+fn dissolve_cost(element: Element) -> isize{
+    cmp::max(1, (element.density() / MASS_PER_STRENGTH).round() as isize)
+}
+
+fn clear_neighbours(of: (isize, isize), field_access: &mut ChunkContext, max_consumed: isize) -> isize {
+    let mut consumed = 0;
     for n in Neighbours::direct_of(of).chain(iter::once(of)){
         if let Ok(Some(element)) = field_access.get(n){
-            match element.get_type() {
-                ElementType::Acid(s) if s > 0 => (),
-                ElementType::Glass => (),
-                _ => {
-                    if thread_rng().gen_bool(DESOLVE_CHANCE){
-                        removed += 1;
-                        field_access.clear(n);
-                        if removed >= max_cleared{
-                            break;
-                        }
-                    }
+            let cost = dissolve_cost(element);
+            if consumed + cost > max_consumed{
+                continue;
+            }
+            if thread_rng().gen_bool(DESOLVE_CHANCE * (1. - element.properties().acid_resistance)){
+                consumed += cost;
+                field_access.clear(n);
+                if consumed >= max_consumed{
+                    break;
                 }
-            };
+            }
         }
     }
-    removed
+    consumed
 }
 
+fn neutralise(of: (isize, isize), field_access: &mut ChunkContext, strength: isize) -> isize {
+    let mut strength = strength;
+    for n in Neighbours::direct_of(of){
+        if strength == 0{
+            break;
+        }
+        if let Ok(Some(Element::Liquid(_, ElementType::Base(base)))) = field_access.get(n){
+            let used = cmp::min(strength, base);
+            strength -= used;
+            if base > used{
+                field_access.set(n, Element::base_with_strength(base - used));
+            }
+            else{
+                field_access.set(n, Element::salt_water());
+            }
+            heat_neighbours(n, field_access);
+        }
+    }
+    strength
+}
 
-pub fn acid_update(mut data: Liquid, strength: isize, position: (isize, isize), field_access: &mut ChunkContext) {
-    let bellow_cord = (position.0, position.1 + 1);
 
-    let copy = data.clone();
+pub fn acid_update(mut data: Liquid, properties: &MaterialProperties, strength: isize, position: (isize, isize), field_access: &mut ChunkContext) {
+    let strength = neutralise(position, field_access, strength);
+    if strength == 0{
+        field_access.set(position, Element::salt_water());
+        return;
+    }
+
+    let gravity = field_access.gravity(position);
+    let (down, side_axis) = (gravity.down(), gravity.side());
+    let bellow_cord = step(position, down, 1);
+
+    if let Some(target) = field_access.gust(position, properties.density){
+        data.stable_time = 0;
+        move_and_clear(position, target, data, strength, field_access);
+        return;
+    }
+
     let move_func = |e: Element| {
         if e.solid().is_some(){
             return false;
         }
-        let prob = thread_rng().gen_bool(f64::max(1. - e.density() / copy.density, copy.slip_through_prob));
-        if let Some(data) = e.liquid(){
-            return copy != *data && prob
+        let prob = thread_rng().gen_bool(f64::max(1. - e.density() / properties.density, properties.slip_through_prob));
+        if e.liquid().is_some(){
+            return e.properties() != *properties && prob
         }
         prob
     };
     
-    if field_access.reachable_empty_or_fitting(bellow_cord, move_func){
+    if !gravity.is_zero() && field_access.reachable_empty_or_fitting(bellow_cord, move_func){
         data.stable_time = 0;
         move_and_clear(position, bellow_cord, data, strength, field_access);
         return;
     }
 
-    if data.stable_time < data.move_time {
+    if !gravity.is_zero() && data.stable_time < properties.move_time {
 
-        let shift = position.0 + data.side;
-        let move_distance = thread_rng().gen_range(1..=data.disperse_distance);
+        let move_distance = thread_rng().gen_range(1..=properties.disperse_distance);
         let mut do_move = false;
-        let mut destination = position.0 + data.side * move_distance;
-        let adjesent = (shift, position.1);
+        let mut destination = step(position, side_axis, data.side * move_distance);
+        let adjesent = step(position, side_axis, data.side);
         
 
-        if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < data.density) {
+        if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < properties.density) {
             do_move = true;
         }
         else{
             data.side = -data.side;
-            let shift = position.0 + data.side;
-
-            let adjesent = (shift, position.1);
+            let adjesent = step(position, side_axis, data.side);
 
-            if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < data.density) {
+            if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < properties.density) {
                 do_move = true;
-                destination = position.0 + data.side * move_distance;
+                destination = step(position, side_axis, data.side * move_distance);
             }
         }
 
         if do_move {
             let destination = get_avalible_point(position, 
-                (destination, position.1), 
+                destination, 
                 field_access, |element| {element.is_none() || move_func(element.unwrap())});
-            let mut new_dest = (destination.0, destination.1 + 1);
-            if !field_access.reachable_empty_or_fitting(new_dest, |e| e.density() < data.density){
+            let mut new_dest = step(destination, down, 1);
+            if !field_access.reachable_empty_or_fitting(new_dest, |e| e.density() < properties.density){
                 new_dest = destination;
             }
             if new_dest != position && field_access.reachable_empty_or_fitting(new_dest, move_func){
@@ -100,7 +134,7 @@ pub fn acid_update(mut data: Liquid, strength: isize, position: (isize, isize),
         }
     }
     
-    if data.stable_time < data.keep_alive_extra_time.or(Some(data.move_time)).unwrap(){
+    if data.stable_time < properties.keep_alive_extra_time.or(Some(properties.move_time)).unwrap(){
         field_access.keep_alive(position);
     }
     data.stable_time += 1;
@@ -0,0 +1,95 @@
+use super::{Element, material::{Material, Phase}, movable_solids::MovableSolid, liquid::Liquid, solid::Solid};
+
+const FALLING: u8 = 1;
+const LEFT: u8 = 2;
+// the direction of the momentum goes in LEFT
+const MOMENTUM_SHIFT: u8 = 2;
+const MOMENTUM_MASK: u8 = 0b1100;
+
+// per-cell state only, everything else comes from the material registry
+#[derive(Clone, Copy, PartialEq)]
+pub struct Cell{
+    material: Material,
+    flags: u8,
+    stable_time: u16,
+    value: i32,
+}
+
+impl From<Element> for Cell {
+    fn from(element: Element) -> Self {
+        let (flags, stable_time) = match element {
+            Element::MovableSolid(d, _) => {
+                let momentum = (d.momentum.unsigned_abs() as u8) << MOMENTUM_SHIFT & MOMENTUM_MASK;
+                (if d.is_falling {FALLING} else {0} | if d.momentum < 0 {LEFT} else {0} | momentum, d.stable_time)
+            },
+            Element::Liquid(d, _) => (if d.side < 0 {LEFT} else {0}, d.stable_time),
+            Element::Solid(_, _) => (0, 0),
+        };
+        let element_type = element.get_type();
+        Cell { material: element_type.material(), 
+            flags, 
+            stable_time: stable_time.clamp(0, u16::MAX as isize) as u16, 
+            value: element_type.value() as i32 }
+    }
+}
+
+impl Cell {
+    pub fn material(&self) -> Material{
+        self.material
+    }
+
+    pub fn element(self) -> Element{
+        let element_type = self.material.with_value(self.value as isize);
+        let stable_time = self.stable_time as isize;
+        match self.material.properties().phase {
+            Phase::MovableSolid => {
+                let momentum = ((self.flags & MOMENTUM_MASK) >> MOMENTUM_SHIFT) as isize;
+                let momentum = if self.flags & LEFT != 0 {-momentum} else {momentum};
+                Element::MovableSolid(MovableSolid{ is_falling: self.flags & FALLING != 0, stable_time, momentum }, element_type)
+            },
+            Phase::Liquid => Element::Liquid(Liquid{ side: if self.flags & LEFT != 0 {-1} else {1}, stable_time }, element_type),
+            Phase::Solid => Element::Solid(Solid{}, element_type),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::elements::{ElementType, movable_solids::MAX_MOMENTUM};
+
+    #[test]
+    fn grains_keep_falling_stable_time_and_momentum(){
+        for momentum in -MAX_MOMENTUM..=MAX_MOMENTUM{
+            for is_falling in [false, true]{
+                let grain = MovableSolid::new().set_falling(is_falling).set_stable_time(17).set_momentum(momentum);
+                match Cell::from(Element::MovableSolid(grain, ElementType::Sand)).element() {
+                    Element::MovableSolid(d, ElementType::Sand) => {
+                        assert_eq!((d.is_falling, d.stable_time, d.momentum), (is_falling, 17, momentum));
+                    },
+                    _ => panic!("sand came back as something else"),
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn liquids_keep_side_and_value(){
+        for side in [-1, 1]{
+            let acid = Element::Liquid(Liquid { side, stable_time: 3 }, ElementType::Acid(7));
+            match Cell::from(acid).element() {
+                Element::Liquid(d, ElementType::Acid(7)) => assert_eq!((d.side, d.stable_time), (side, 3)),
+                _ => panic!("acid came back as something else"),
+            }
+        }
+    }
+
+    #[test]
+    fn stable_time_is_clamped_to_what_fits(){
+        let grain = MovableSolid::new().set_stable_time(isize::MAX);
+        match Cell::from(Element::MovableSolid(grain, ElementType::Sand)).element() {
+            Element::MovableSolid(d, _) => assert_eq!(d.stable_time, u16::MAX as isize),
+            _ => panic!("sand came back as something else"),
+        }
+    }
+}
@@ -0,0 +1,70 @@
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+
+use super::{Element, ElementType, heat::heat_neighbours};
+
+pub const SPARK_TIME: isize = 3;
+pub const SPARK_COOLDOWN: isize = 4;
+pub const LAMP_GLOW_TIME: isize = 30;
+pub const HEATER_HEAT_TIME: isize = 30;
+
+// positive while sparked, negative while cooling down so a spark can't flow back
+fn sparked(element: Element) -> Option<Element>{
+    match element {
+        Element::Solid(d, ElementType::Metal(0)) => Some(Element::Solid(d, ElementType::Metal(SPARK_TIME))),
+        Element::Liquid(d, ElementType::Water(0)) => Some(Element::Liquid(d, ElementType::Water(SPARK_TIME))),
+        Element::Liquid(d, ElementType::SaltWater(0)) => Some(Element::Liquid(d, ElementType::SaltWater(SPARK_TIME))),
+        Element::Solid(d, ElementType::Lamp(_)) => Some(Element::Solid(d, ElementType::Lamp(LAMP_GLOW_TIME))),
+        Element::Solid(d, ElementType::Heater(_)) => Some(Element::Solid(d, ElementType::Heater(HEATER_HEAT_TIME))),
+        _ => None,
+    }
+}
+
+fn spark_neighbours(of: (isize, isize), field_access: &mut ChunkContext){
+    for n in Neighbours::direct_of(of){
+        if let Ok(Some(element)) = field_access.get(n){
+            if let Some(sparked) = sparked(element){
+                field_access.set_static(n, sparked);
+                field_access.keep_alive(n);
+            }
+        }
+    }
+}
+
+pub fn conduct(charge: isize, position: (isize, isize), field_access: &mut ChunkContext) -> isize{
+    let charge = if charge > 0 {
+        spark_neighbours(position, field_access);
+        if charge == 1 {-SPARK_COOLDOWN} else {charge - 1}
+    }
+    else if charge < 0 {
+        charge + 1
+    }
+    else {
+        0
+    };
+    if charge != 0 {
+        field_access.keep_alive(position);
+    }
+    charge
+}
+
+pub fn battery_update(position: (isize, isize), field_access: &mut ChunkContext){
+    spark_neighbours(position, field_access);
+    field_access.keep_alive(position);
+}
+
+pub fn lamp_update(glow: isize, position: (isize, isize), field_access: &mut ChunkContext) -> isize{
+    if glow > 0 {
+        field_access.keep_alive(position);
+        return glow - 1;
+    }
+    0
+}
+
+pub fn heater_update(heat: isize, position: (isize, isize), field_access: &mut ChunkContext) -> isize{
+    if heat > 0 {
+        heat_neighbours(position, field_access);
+        field_access.keep_alive(position);
+        return heat - 1;
+    }
+    0
+}
@@ -0,0 +1,25 @@
+use rand::{thread_rng, Rng};
+
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+
+use super::{Element, ElementType};
+
+const EVAPORATION_CHANCE: f64 = 0.05;
+
+pub fn heat(position: (isize, isize), field_access: &mut ChunkContext){
+    if let Ok(Some(element)) = field_access.get(position){
+        match element.get_type() {
+            ElementType::Water(_) if thread_rng().gen_bool(EVAPORATION_CHANCE) => field_access.clear(position),
+            ElementType::SaltWater(_) if thread_rng().gen_bool(EVAPORATION_CHANCE) => field_access.set(position, Element::salt()),
+            ElementType::WetSand(_) => field_access.set(position, Element::sand()),
+            ElementType::Ice => field_access.set(position, Element::water()),
+            _ => (),
+        }
+    }
+}
+
+pub fn heat_neighbours(of: (isize, isize), field_access: &mut ChunkContext){
+    for n in Neighbours::of(of){
+        heat(n, field_access);
+    }
+}
@@ -0,0 +1,35 @@
+use rand::{thread_rng, Rng};
+
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+
+use super::{solid::Solid, Element, ElementData, ElementType, material::MaterialProperties};
+
+const FREEZE_CHANCE: f64 = 0.01;
+const MELT_CHANCE: f64 = 0.02;
+
+// salt water never freezes, ice touching salt or salt water slowly melts
+pub fn ice_update(data: Solid, properties: &MaterialProperties, position: (isize, isize), field_access: &mut ChunkContext){
+    let mut active = false;
+    for n in Neighbours::direct_of(position){
+        match field_access.get(n).map(|e| e.map(|e| e.get_type())) {
+            Ok(Some(ElementType::Water(_))) => {
+                active = true;
+                if thread_rng().gen_bool(FREEZE_CHANCE){
+                    field_access.set(n, Element::ice());
+                }
+            },
+            Ok(Some(ElementType::Salt)) | Ok(Some(ElementType::SaltWater(_))) => {
+                active = true;
+                if thread_rng().gen_bool(MELT_CHANCE){
+                    field_access.set(position, Element::water());
+                    return;
+                }
+            },
+            _ => (),
+        }
+    }
+    if active{
+        field_access.keep_alive(position);
+    }
+    data.update(properties, position, field_access, |d,_,_| Element::Solid(d, ElementType::Ice));
+}
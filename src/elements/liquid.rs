@@ -1,89 +1,115 @@
 use rand::{thread_rng, Rng};
 
-use crate::field::chunk_context::ChunkContext;
+use crate::field::{chunk_context::ChunkContext, gravity::step, neighbours::Neighbours};
 
-use super::{ElementData, Element, get_avalible_point};
+use super::{ElementData, Element, get_avalible_point, material::{MaterialProperties, Material}};
 
 
 #[derive(Clone, Copy)]
 pub struct Liquid{
     pub side: isize,
-    pub disperse_distance: isize,
-    pub move_time: isize,
-    pub keep_alive_extra_time: Option<isize>,
-    pub density: f64,
     pub stable_time: isize,
-    pub slip_through_prob: f64,
 }
 
-impl PartialEq for Liquid {
-    fn eq(&self, other: &Self) -> bool {
-        self.move_time == other.move_time && self.disperse_distance == other.disperse_distance && 
-        self.density == other.density && self.slip_through_prob == other.slip_through_prob
+impl Liquid {
+    pub fn new() -> Liquid{
+        Liquid { side: if thread_rng().gen_bool(0.5) {-1} else {1}, stable_time: 0 }
     }
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+impl Default for Liquid {
+    fn default() -> Self {
+        Liquid::new()
     }
 }
 
+fn own_kind_around(position: (isize, isize), except: (isize, isize), material: Material, field_access: &ChunkContext) -> usize{
+    Neighbours::of(position)
+        .filter(|n| *n != except)
+        .filter(|n| matches!(field_access.get(*n), Ok(Some(e)) if e.get_type().material() == material))
+        .count()
+}
+
 impl ElementData for Liquid {
     type Item = Liquid;
     
-    fn update(mut self, position: (isize, isize), field_access: &mut ChunkContext, convert_fun: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element) {
-        let bellow_cord = (position.0, position.1 + 1);
+    fn update(mut self, properties: &MaterialProperties, position: (isize, isize), field_access: &mut ChunkContext, convert_fun: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element) {
+        let gravity = field_access.gravity(position);
+        let (down, side_axis) = (gravity.down(), gravity.side());
+        let bellow_cord = step(position, down, 1);
+
+        if let Some(target) = field_access.gust(position, properties.density){
+            self.stable_time = 0;
+            field_access.move_from_to(position, target, convert_fun(self, target, field_access));
+            return;
+        }
 
-        let copy = self.clone();
         let move_func = |e: Element| {
             if e.solid().is_some(){
                 return false;
             }
-            let prob = thread_rng().gen_bool(f64::max(1. - e.density() / copy.density, copy.slip_through_prob));
-            if let Some(data) = e.liquid(){
-                return copy != *data && prob
+            let prob = thread_rng().gen_bool(f64::max(1. - e.density() / properties.density, properties.slip_through_prob));
+            if e.liquid().is_some(){
+                return e.properties() != *properties && prob
             }
             prob
         };
         
-        if field_access.reachable_empty_or_fitting(bellow_cord, move_func){
+        if !gravity.is_zero() && field_access.reachable_empty_or_fitting(bellow_cord, move_func){
+            // viscous liquid drags along whatever it slides past
+            let sheared = [1, -1].into_iter()
+                .any(|side| matches!(field_access.get(step(position, side_axis, side)), Ok(Some(_))));
+            if sheared && properties.viscosity > 0. && thread_rng().gen_bool(properties.viscosity){
+                field_access.keep_alive(position);
+                return;
+            }
             self.stable_time = 0;
             field_access.move_from_to(position, bellow_cord, convert_fun(self, bellow_cord, field_access));
             return;
         }
 
-        if self.stable_time < self.move_time {
+        if !gravity.is_zero() && self.stable_time < properties.move_time {
+            if properties.viscosity > 0. && thread_rng().gen_bool(properties.viscosity){
+                field_access.keep_alive(position);
+                return;
+            }
 
-            let shift = position.0 + self.side;
-            let move_distance = thread_rng().gen_range(1..=self.disperse_distance);
+            let move_distance = thread_rng().gen_range(1..=properties.disperse_distance);
             let mut do_move = false;
-            let mut destination = position.0 + self.side * move_distance;
-            let adjesent = (shift, position.1);
+            let mut destination = step(position, side_axis, self.side * move_distance);
+            let adjesent = step(position, side_axis, self.side);
             
 
-            if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < self.density) {
+            if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < properties.density) {
                 do_move = true;
             }
             else{
                 self.side = -self.side;
-                let shift = position.0 + self.side;
+                let adjesent = step(position, side_axis, self.side);
 
-                let adjesent = (shift, position.1);
-
-                if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < self.density) {
+                if field_access.reachable_empty_or_fitting(adjesent, |e| e.density() < properties.density) {
                     do_move = true;
-                    destination = position.0 + self.side * move_distance;
+                    destination = step(position, side_axis, self.side * move_distance);
                 }
             }
 
             if do_move {
                 let destination = get_avalible_point(position, 
-                    (destination, position.1), 
+                    destination, 
                     field_access, |element| {element.is_none() || move_func(element.unwrap())});
-                let mut new_dest = (destination.0, destination.1 + 1);
-                if !field_access.reachable_empty_or_fitting(new_dest, |e| e.density() < self.density){
+                let mut new_dest = step(destination, down, 1);
+                if !field_access.reachable_empty_or_fitting(new_dest, |e| e.density() < properties.density){
                     new_dest = destination;
                 }
-                if new_dest != position && field_access.reachable_empty_or_fitting(new_dest, move_func){
+                let breaks_away = properties.cohesion > 0. && match field_access.get(position) {
+                    Ok(Some(current)) => {
+                        let material = current.get_type().material();
+                        (own_kind_around(new_dest, position, material, field_access) as f64) <
+                            own_kind_around(position, position, material, field_access) as f64 * properties.cohesion
+                    },
+                    _ => false,
+                };
+                if new_dest != position && !breaks_away && field_access.reachable_empty_or_fitting(new_dest, move_func){
                     self.stable_time = 0;
                     field_access.move_from_to(position, new_dest, convert_fun(self, new_dest, field_access));
                     return;
@@ -91,19 +117,15 @@ impl ElementData for Liquid {
             }
         }
         
-        if self.stable_time < self.keep_alive_extra_time.or(Some(self.move_time)).unwrap(){
+        if self.stable_time < properties.keep_alive_extra_time.or(Some(properties.move_time)).unwrap(){
             field_access.keep_alive(position);
         }
         self.stable_time += 1;
         field_access.set_static(position, convert_fun(self, position, field_access));
     }
 
-    fn refresh(mut self) -> Self::Item {
+    fn refresh(mut self, _: &MaterialProperties) -> Self::Item {
         self.stable_time = 0;
         self
     }
-
-    fn density(&self) -> f64 {
-        self.density
-    }
 }
\ No newline at end of file
@@ -0,0 +1,190 @@
+use super::ElementType;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Phase{
+    MovableSolid,
+    Liquid,
+    Solid,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub struct MaterialProperties{
+    pub phase: Phase,
+    pub density: f64,
+    pub move_time: isize,
+    // degrees
+    pub repose_angle: f32,
+    pub friction: f64,
+    pub unstuck_speed: isize,
+    pub disperse_distance: isize,
+    pub slip_through_prob: f64,
+    pub keep_alive_extra_time: Option<isize>,
+    // chance a liquid skips spreading this tick
+    pub viscosity: f64,
+    // high values bead a liquid up into droplets
+    pub cohesion: f64,
+    // 1 means the material is never dissolved by acid
+    pub acid_resistance: f64,
+    // cells a solid can reach from its support, None outside of structures
+    pub structural_strength: Option<isize>,
+}
+
+const fn movable_solid(density: f64, repose_angle: f32, friction: f64, move_time: isize, disperse_distance: isize) -> MaterialProperties{
+    MaterialProperties { phase: Phase::MovableSolid, density, move_time, repose_angle, friction, unstuck_speed: move_time,
+        disperse_distance, slip_through_prob: 0., keep_alive_extra_time: None, viscosity: 0., cohesion: 0.,
+        acid_resistance: 0., structural_strength: None }
+}
+
+const fn liquid(density: f64, move_time: isize, disperse_distance: isize, slip_through_prob: f64) -> MaterialProperties{
+    MaterialProperties { phase: Phase::Liquid, density, move_time, repose_angle: 0., friction: 0., unstuck_speed: 0,
+        disperse_distance, slip_through_prob, keep_alive_extra_time: None, viscosity: 0., cohesion: 0.,
+        acid_resistance: 0., structural_strength: None }
+}
+
+const fn solid(density: f64) -> MaterialProperties{
+    MaterialProperties { phase: Phase::Solid, density, move_time: 0, repose_angle: 0., friction: 0., unstuck_speed: 0,
+        disperse_distance: 0, slip_through_prob: 0., keep_alive_extra_time: None, viscosity: 0., cohesion: 0.,
+        acid_resistance: 0., structural_strength: None }
+}
+
+pub const WET_SAND_DRY_TIME: isize = 700;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Material{
+    Sand,
+    WetSand,
+    Water,
+    Oil,
+    Acid,
+    Block,
+    Glass,
+    Metal,
+    Battery,
+    Lamp,
+    Heater,
+    Salt,
+    SaltWater,
+    Ice,
+    Alcohol,
+    Base,
+    Wood,
+    Stone,
+    Debris,
+    Anchor,
+    Honey,
+    Mercury,
+    Slime,
+}
+
+// indexed by material id
+static REGISTRY: [(Material, MaterialProperties); 23] = [
+    (Material::Sand, movable_solid(10., 34., 0.3, 20, 4)),
+    (Material::WetSand, MaterialProperties { keep_alive_extra_time: Some(WET_SAND_DRY_TIME), ..movable_solid(10.1, 60., 0.8, 10, 2) }),
+    (Material::Water, MaterialProperties { acid_resistance: 1., ..liquid(7., 100, 10, 0.02) }),
+    (Material::Oil, MaterialProperties { acid_resistance: 0.5, ..liquid(2., 60, 2, 0.) }),
+    (Material::Acid, MaterialProperties { acid_resistance: 1., ..liquid(4., 100, 3, 0.) }),
+    (Material::Block, MaterialProperties { acid_resistance: 0.3, structural_strength: Some(6), ..solid(50.) }),
+    (Material::Glass, MaterialProperties { acid_resistance: 1., structural_strength: Some(2), ..solid(50.) }),
+    (Material::Metal, MaterialProperties { acid_resistance: 0.5, structural_strength: Some(12), ..solid(80.) }),
+    (Material::Battery, MaterialProperties { acid_resistance: 0.5, structural_strength: Some(4), ..solid(50.) }),
+    (Material::Lamp, MaterialProperties { acid_resistance: 0.7, structural_strength: Some(4), ..solid(50.) }),
+    (Material::Heater, MaterialProperties { acid_resistance: 0.5, structural_strength: Some(4), ..solid(50.) }),
+    (Material::Salt, movable_solid(9., 30., 0.2, 20, 4)),
+    // salt water is what neutralising leaves behind, acid must not eat it again
+    (Material::SaltWater, MaterialProperties { acid_resistance: 1., ..liquid(8., 100, 8, 0.02) }),
+    (Material::Ice, MaterialProperties { acid_resistance: 1., structural_strength: Some(3), ..solid(6.5) }),
+    (Material::Alcohol, MaterialProperties { acid_resistance: 1., ..liquid(7., 100, 10, 0.02) }),
+    (Material::Base, MaterialProperties { acid_resistance: 1., ..liquid(7.5, 100, 3, 0.) }),
+    (Material::Wood, solid(30.)),
+    (Material::Stone, MaterialProperties { acid_resistance: 0.3, ..solid(60.) }),
+    (Material::Debris, movable_solid(12., 42., 0.5, 15, 3)),
+    (Material::Anchor, MaterialProperties { acid_resistance: 1., structural_strength: Some(isize::MAX), ..solid(100.) }),
+    (Material::Honey, MaterialProperties { viscosity: 0.9, cohesion: 0.3, acid_resistance: 0.5, ..liquid(8.5, 100, 2, 0.) }),
+    (Material::Mercury, MaterialProperties { viscosity: 0.1, cohesion: 0.9, acid_resistance: 1., ..liquid(40., 100, 4, 0.) }),
+    (Material::Slime, MaterialProperties { viscosity: 0.7, cohesion: 0.7, acid_resistance: 0.5, ..liquid(7.5, 100, 3, 0.) }),
+];
+
+const _: () = {
+    let mut i = 0;
+    while i < REGISTRY.len(){
+        assert!(REGISTRY[i].0 as usize == i, "REGISTRY is out of order with Material");
+        i += 1;
+    }
+};
+
+impl Material {
+    pub fn id(self) -> u8{
+        self as u8
+    }
+
+    pub fn properties(self) -> &'static MaterialProperties{
+        &REGISTRY[self as usize].1
+    }
+
+    pub fn with_value(self, value: isize) -> ElementType{
+        match self {
+            Material::Sand => ElementType::Sand,
+            Material::WetSand => ElementType::WetSand(value),
+            Material::Water => ElementType::Water(value),
+            Material::Oil => ElementType::Oil,
+            Material::Acid => ElementType::Acid(value),
+            Material::Block => ElementType::Block,
+            Material::Glass => ElementType::Glass,
+            Material::Metal => ElementType::Metal(value),
+            Material::Battery => ElementType::Battery,
+            Material::Lamp => ElementType::Lamp(value),
+            Material::Heater => ElementType::Heater(value),
+            Material::Salt => ElementType::Salt,
+            Material::SaltWater => ElementType::SaltWater(value),
+            Material::Ice => ElementType::Ice,
+            Material::Alcohol => ElementType::Alcohol(value),
+            Material::Base => ElementType::Base(value),
+            Material::Wood => ElementType::Wood,
+            Material::Stone => ElementType::Stone,
+            Material::Debris => ElementType::Debris,
+            Material::Anchor => ElementType::Anchor,
+            Material::Honey => ElementType::Honey,
+            Material::Mercury => ElementType::Mercury,
+            Material::Slime => ElementType::Slime,
+        }
+    }
+}
+
+impl ElementType {
+    pub fn material(&self) -> Material{
+        match self {
+            ElementType::Sand => Material::Sand,
+            ElementType::WetSand(_) => Material::WetSand,
+            ElementType::Water(_) => Material::Water,
+            ElementType::Oil => Material::Oil,
+            ElementType::Acid(_) => Material::Acid,
+            ElementType::Block => Material::Block,
+            ElementType::Glass => Material::Glass,
+            ElementType::Metal(_) => Material::Metal,
+            ElementType::Battery => Material::Battery,
+            ElementType::Lamp(_) => Material::Lamp,
+            ElementType::Heater(_) => Material::Heater,
+            ElementType::Salt => Material::Salt,
+            ElementType::SaltWater(_) => Material::SaltWater,
+            ElementType::Ice => Material::Ice,
+            ElementType::Alcohol(_) => Material::Alcohol,
+            ElementType::Base(_) => Material::Base,
+            ElementType::Wood => Material::Wood,
+            ElementType::Stone => Material::Stone,
+            ElementType::Debris => Material::Debris,
+            ElementType::Anchor => Material::Anchor,
+            ElementType::Honey => Material::Honey,
+            ElementType::Mercury => Material::Mercury,
+            ElementType::Slime => Material::Slime,
+        }
+    }
+
+    pub fn value(&self) -> isize{
+        match *self {
+            ElementType::WetSand(v) | ElementType::Water(v) | ElementType::Acid(v) | ElementType::Metal(v) |
+            ElementType::Lamp(v) | ElementType::Heater(v) | ElementType::SaltWater(v) | ElementType::Alcohol(v) |
+            ElementType::Base(v) => v,
+            _ => 0,
+        }
+    }
+}
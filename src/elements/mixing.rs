@@ -0,0 +1,119 @@
+use rand::{thread_rng, Rng, seq::IteratorRandom};
+
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+
+use super::{Element, ElementType};
+
+const MIX_CHANCE: f64 = 0.1;
+pub const PURE_ALCOHOL: isize = 100;
+const MIN_ALCOHOL_CONCENTRATION: isize = 5;
+
+#[derive(Clone, Copy, PartialEq)]
+enum Solute{
+    Acid,
+    Base,
+    Alcohol,
+}
+
+pub fn miscible(a: ElementType, b: ElementType) -> bool{
+    matches!((a, b),
+        (ElementType::Water(_), ElementType::Acid(_)) | (ElementType::Acid(_), ElementType::Water(_)) |
+        (ElementType::Water(_), ElementType::Alcohol(_)) | (ElementType::Alcohol(_), ElementType::Water(_)) |
+        (ElementType::Water(_), ElementType::Base(_)) | (ElementType::Base(_), ElementType::Water(_)) |
+        (ElementType::Acid(_), ElementType::Acid(_)) |
+        (ElementType::Base(_), ElementType::Base(_)) |
+        (ElementType::Alcohol(_), ElementType::Alcohol(_)))
+}
+
+// water is treated as a solution of anything with zero concentration
+fn concentration(element_type: ElementType) -> (Option<Solute>, isize){
+    match element_type {
+        ElementType::Acid(strength) => (Some(Solute::Acid), strength),
+        ElementType::Base(strength) => (Some(Solute::Base), strength),
+        ElementType::Alcohol(concentration) => (Some(Solute::Alcohol), concentration),
+        _ => (None, 0),
+    }
+}
+
+fn diluted(original: Element, solute: Solute, concentration: isize) -> Element{
+    let side = match original {
+        Element::Liquid(d, _) => d.side,
+        _ => 1,
+    };
+    let element = match solute {
+        Solute::Acid if concentration > 0 => Element::acid_with_strength(concentration),
+        Solute::Base if concentration > 0 => Element::base_with_strength(concentration),
+        Solute::Alcohol if concentration >= MIN_ALCOHOL_CONCENTRATION => Element::diluted_alcohol(concentration),
+        _ => Element::water(),
+    };
+    match element {
+        Element::Liquid(mut d, t) => {
+            d.side = side;
+            Element::Liquid(d, t)
+        },
+        e => e,
+    }
+}
+
+fn blend(a: Element, b: Element) -> Option<(Element, Element)>{
+    if !miscible(a.get_type(), b.get_type()){
+        return None;
+    }
+    let (solute_a, concentration_a) = concentration(a.get_type());
+    let (solute_b, concentration_b) = concentration(b.get_type());
+    let solute = solute_a.or(solute_b)?;
+    if (concentration_a - concentration_b).abs() <= 1 {
+        return None;
+    }
+    let total = concentration_a + concentration_b;
+    Some((diluted(a, solute, total - total / 2), diluted(b, solute, total / 2)))
+}
+
+pub fn mix(element: Element, position: (isize, isize), field_access: &mut ChunkContext) -> Element{
+    if !thread_rng().gen_bool(MIX_CHANCE){
+        return element;
+    }
+    let neighbour = Neighbours::direct_of(position).choose(&mut thread_rng()).unwrap();
+    if let Ok(Some(other)) = field_access.get(neighbour){
+        if let Some((mixed, other_mixed)) = blend(element, other){
+            field_access.set(neighbour, other_mixed);
+            field_access.keep_alive(neighbour);
+            field_access.keep_alive(position);
+            return mixed;
+        }
+    }
+    element
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn acid_is_split_evenly_with_water(){
+        let (a, b) = blend(Element::acid_with_strength(10), Element::water()).unwrap();
+        assert!(matches!(a.get_type(), ElementType::Acid(5)));
+        assert!(matches!(b.get_type(), ElementType::Acid(5)));
+    }
+
+    #[test]
+    fn odd_strength_leaves_the_extra_unit_with_the_first(){
+        let (a, b) = blend(Element::water(), Element::acid_with_strength(3)).unwrap();
+        assert!(matches!(a.get_type(), ElementType::Acid(2)));
+        assert!(matches!(b.get_type(), ElementType::Acid(1)));
+    }
+
+    #[test]
+    fn immiscible_or_settled_pairs_do_not_blend(){
+        assert!(blend(Element::water(), Element::oil()).is_none());
+        assert!(blend(Element::water(), Element::water()).is_none());
+        assert!(blend(Element::acid_with_strength(5), Element::acid_with_strength(4)).is_none());
+    }
+
+    #[test]
+    fn weak_alcohol_turns_into_water(){
+        let (a, b) = blend(Element::diluted_alcohol(6), Element::water()).unwrap();
+        assert!(matches!(a.get_type(), ElementType::Water(_)));
+        assert!(matches!(b.get_type(), ElementType::Water(_)));
+    }
+}
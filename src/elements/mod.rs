@@ -1,27 +1,51 @@
 use std::iter;
 
 use bresenham::Bresenham;
-use rand::{random, thread_rng, Rng};
 
 use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
 
-use self::{movable_solids::MovableSolid, liquid::Liquid, elements_convert::{sand_convert}, solid::Solid, acid::acid_update};
+use self::{movable_solids::MovableSolid, liquid::Liquid, elements_convert::{sand_convert}, solid::Solid, acid::acid_update, 
+    electricity::{conduct, battery_update, lamp_update, heater_update}, salt::salt_update, ice::ice_update,
+    mixing::{mix, PURE_ALCOHOL}, material::{MaterialProperties, WET_SAND_DRY_TIME}, cell::Cell};
 
 pub mod movable_solids;
 pub mod liquid;
 mod elements_convert;
 pub mod solid;
+pub mod material;
+pub mod cell;
 mod acid;
+mod heat;
+mod electricity;
+mod salt;
+mod ice;
+mod mixing;
 
 #[derive(Clone, Copy)]
 pub enum ElementType{
     Sand, 
     WetSand(isize),
-    Water,
+    Water(isize),
     Oil,
     Acid(isize),
     Block,
-    Glass
+    Glass,
+    Metal(isize),
+    Battery,
+    Lamp(isize),
+    Heater(isize),
+    Salt,
+    SaltWater(isize),
+    Ice,
+    Alcohol(isize),
+    Base(isize),
+    Wood,
+    Stone,
+    Debris,
+    Anchor,
+    Honey,
+    Mercury,
+    Slime,
 }
 
 #[derive(Clone, Copy)]
@@ -34,87 +58,114 @@ pub enum Element{
 pub trait ElementData{
     type Item;
 
-    fn update(self, position: (isize, isize), field_access: &mut ChunkContext, to_element: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element);
-    fn refresh(self) -> Self::Item;
-    fn density(&self) -> f64;
+    fn update(self, properties: &MaterialProperties, position: (isize, isize), field_access: &mut ChunkContext, to_element: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element);
+    fn refresh(self, properties: &MaterialProperties) -> Self::Item;
 }
 
-const WET_SAND_DRY_TIME: isize = 700;
-
 impl Element {
 
     pub fn sand() -> Element{
-        Element::MovableSolid(MovableSolid{ is_falling: true, 
-            stable_time: 0, 
-            flow_coefficient: 2.,
-            move_time: 20,
-            unstuck_speed: 20,
-            disperse_distance: 3,
-            density: 10.,
-            slip_through_prob: 0.,
-            keep_alive_extra_time: None,
-         }, ElementType::Sand)
+        Element::MovableSolid(MovableSolid::new(), ElementType::Sand)
     }
 
     pub fn acid() -> Element{
-        Element::Liquid(Liquid{
-        side: if thread_rng().gen_bool(0.5) {-1} else {1},
-        disperse_distance: 3,
-        move_time: 100,
-        keep_alive_extra_time: None,
-        density: 4.,
-        stable_time: 0,
-        slip_through_prob: 0.,
-        }, ElementType::Acid(5))
+        Element::acid_with_strength(5)
+    }
+
+    pub fn acid_with_strength(strength: isize) -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Acid(strength))
+    }
+
+    pub fn base() -> Element{
+        Element::base_with_strength(5)
+    }
+
+    pub fn base_with_strength(strength: isize) -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Base(strength))
     }
 
     pub fn glass() -> Element{
-        Element::Solid(Solid{
-            density: 50.,
-        }, ElementType::Glass)
+        Element::Solid(Solid{}, ElementType::Glass)
     }
 
     pub fn water() -> Element{
-        Element::Liquid(Liquid{
-            stable_time: 0,
-            move_time: 100,
-            disperse_distance: 10,
-            side: if thread_rng().gen_bool(0.5) {-1} else {1},
-            density: 7.,
-            slip_through_prob: 0.02,
-            keep_alive_extra_time: None,
-        }, ElementType::Water)
+        Element::Liquid(Liquid::new(), ElementType::Water(0))
+    }
+
+    pub fn salt_water() -> Element{
+        Element::Liquid(Liquid::new(), ElementType::SaltWater(0))
     }
 
     pub fn wet_sand() -> Element{
-        Element::MovableSolid(MovableSolid{ is_falling: true, 
-            stable_time: 0, 
-            flow_coefficient: 0.3,
-            move_time: 10,
-            unstuck_speed: 10,
-            disperse_distance: 2,
-            density: 10.1,
-            slip_through_prob: 0.,
-            keep_alive_extra_time: Some(WET_SAND_DRY_TIME),
-         }, ElementType::WetSand(0))
+        Element::MovableSolid(MovableSolid::new(), ElementType::WetSand(0))
     }
 
     pub fn oil() -> Element{
-        Element::Liquid(Liquid{
-            stable_time: 0,
-            move_time: 60,
-            disperse_distance: 2,
-            side: if thread_rng().gen_bool(0.5) {-1} else {1},
-            density: 2.,
-            slip_through_prob: 0.,
-            keep_alive_extra_time: None,
-        }, ElementType::Oil)
+        Element::Liquid(Liquid::new(), ElementType::Oil)
     }
 
     pub fn block() -> Element{
-        Element::Solid(Solid{
-            density: 50.,
-        }, ElementType::Block)
+        Element::Solid(Solid{}, ElementType::Block)
+    }
+
+    pub fn metal() -> Element{
+        Element::Solid(Solid{}, ElementType::Metal(0))
+    }
+
+    pub fn battery() -> Element{
+        Element::Solid(Solid{}, ElementType::Battery)
+    }
+
+    pub fn lamp() -> Element{
+        Element::Solid(Solid{}, ElementType::Lamp(0))
+    }
+
+    pub fn heater() -> Element{
+        Element::Solid(Solid{}, ElementType::Heater(0))
+    }
+
+    pub fn salt() -> Element{
+        Element::MovableSolid(MovableSolid::new(), ElementType::Salt)
+    }
+
+    pub fn ice() -> Element{
+        Element::Solid(Solid{}, ElementType::Ice)
+    }
+
+    pub fn alcohol() -> Element{
+        Element::diluted_alcohol(PURE_ALCOHOL)
+    }
+
+    pub fn diluted_alcohol(concentration: isize) -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Alcohol(concentration))
+    }
+
+    pub fn wood() -> Element{
+        Element::Solid(Solid{}, ElementType::Wood)
+    }
+
+    pub fn stone() -> Element{
+        Element::Solid(Solid{}, ElementType::Stone)
+    }
+
+    pub fn debris() -> Element{
+        Element::MovableSolid(MovableSolid::new(), ElementType::Debris)
+    }
+
+    pub fn anchor() -> Element{
+        Element::Solid(Solid{}, ElementType::Anchor)
+    }
+
+    pub fn honey() -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Honey)
+    }
+
+    pub fn mercury() -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Mercury)
+    }
+
+    pub fn slime() -> Element{
+        Element::Liquid(Liquid::new(), ElementType::Slime)
     }
 
     pub fn get_type(&self) -> ElementType{
@@ -128,20 +179,52 @@ impl Element {
     pub fn get_color(&self) -> [u8; 4]{
         match self.get_type() {
             ElementType::Sand => [0xff, 0xff, 0x00, 0xff],
-            ElementType::Water => [0x00, 0x50, 0xff, 0xff],
+            ElementType::Water(c) if c > 0 => [0x80, 0xc0, 0xff, 0xff],
+            ElementType::Water(_) => [0x00, 0x50, 0xff, 0xff],
             ElementType::WetSand(_) => [0xb3, 0xb3, 0x00, 0xff],
             ElementType::Oil => [0x33, 0x33, 0x10, 0xff],
             ElementType::Block => [0xb3, 0xb3, 0xb3, 0xff],
+            ElementType::Acid(s) if s <= 2 => [0x80, 0xf0, 0x60, 0xff],
             ElementType::Acid(_) => [0x39, 0xe6, 0x00, 0xff],
             ElementType::Glass => [0xb3, 0xff, 0xff, 0xff],
+            ElementType::Metal(c) if c > 0 => [0xff, 0xff, 0x80, 0xff],
+            ElementType::Metal(_) => [0x70, 0x80, 0x90, 0xff],
+            ElementType::Battery => [0xd0, 0x40, 0x40, 0xff],
+            ElementType::Lamp(g) if g > 0 => [0xff, 0xf0, 0x90, 0xff],
+            ElementType::Lamp(_) => [0x50, 0x50, 0x30, 0xff],
+            ElementType::Heater(h) if h > 0 => [0xff, 0x50, 0x20, 0xff],
+            ElementType::Heater(_) => [0x80, 0x30, 0x20, 0xff],
+            ElementType::Salt => [0xf0, 0xf0, 0xf0, 0xff],
+            ElementType::SaltWater(c) if c > 0 => [0x90, 0xc8, 0xff, 0xff],
+            ElementType::SaltWater(_) => [0x20, 0x70, 0xd0, 0xff],
+            ElementType::Ice => [0xc8, 0xe8, 0xff, 0xff],
+            ElementType::Alcohol(c) => {
+                let t = c as f32 / PURE_ALCOHOL as f32;
+                let lerp = |water: u8, alcohol: u8| (water as f32 + (alcohol as f32 - water as f32) * t) as u8;
+                [lerp(0x00, 0xd0), lerp(0x50, 0xe8), lerp(0xff, 0xf0), 0xff]
+            },
+            ElementType::Base(s) if s <= 2 => [0xc8, 0x98, 0xf0, 0xff],
+            ElementType::Base(_) => [0xa0, 0x50, 0xe0, 0xff],
+            ElementType::Wood => [0x9a, 0x6a, 0x3a, 0xff],
+            ElementType::Stone => [0x80, 0x80, 0x88, 0xff],
+            ElementType::Debris => [0x90, 0x80, 0x70, 0xff],
+            ElementType::Anchor => [0x40, 0x38, 0x30, 0xff],
+            ElementType::Honey => [0xe0, 0xa0, 0x20, 0xff],
+            ElementType::Mercury => [0xc0, 0xc0, 0xc8, 0xff],
+            ElementType::Slime => [0x60, 0xd0, 0x40, 0xff],
         }
     }
 
     pub fn update(self, position: (isize, isize), field_access: &mut ChunkContext){
-        match self {
-            Element::MovableSolid(data, ElementType::WetSand(t)) => data.update(position, field_access, |d, p, f| {
+        let element = match self {
+            Element::Liquid(_, _) => mix(self, position, field_access),
+            _ => self,
+        };
+        let properties = element.properties();
+        match element {
+            Element::MovableSolid(data, ElementType::WetSand(t)) => data.update(&properties, position, field_access, |d, p, f| {
                 let new_t = if Neighbours::direct_of(p).any(|n| {f.reachable_and_fitting(n, |e|{
-                    if let Some(Element::Liquid(_, ElementType::Water)) = e {true} else {false}
+                    if let Some(Element::Liquid(_, ElementType::Water(_))) = e {true} else {false}
                 })}) {0} else {t + 1};
                 if new_t >= WET_SAND_DRY_TIME {
                     Element::sand()
@@ -151,32 +234,83 @@ impl Element {
                 }
             }),
 
-            Element::Liquid(data, ElementType::Acid(strength)) => acid_update(data, strength, position, field_access),
+            Element::Liquid(data, ElementType::Acid(strength)) => acid_update(data, &properties, strength, position, field_access),
+
+            Element::MovableSolid(data, ElementType::Sand) => data.update(&properties, position, field_access, sand_convert),
+
+            Element::Liquid(data, ElementType::Water(charge)) => {
+                let charge = conduct(charge, position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Liquid(d, ElementType::Water(charge)))
+            },
 
-            Element::MovableSolid(data, ElementType::Sand) => data.update(position, field_access, sand_convert),
+            Element::Liquid(data, ElementType::SaltWater(charge)) => {
+                let charge = conduct(charge, position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Liquid(d, ElementType::SaltWater(charge)))
+            },
+
+            Element::MovableSolid(data, ElementType::Salt) => salt_update(data, &properties, position, field_access),
+
+            Element::Solid(data, ElementType::Ice) => ice_update(data, &properties, position, field_access),
+
+            Element::Solid(data, ElementType::Metal(charge)) => {
+                let charge = conduct(charge, position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Solid(d, ElementType::Metal(charge)))
+            },
+
+            Element::Solid(data, ElementType::Battery) => {
+                battery_update(position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Solid(d, ElementType::Battery))
+            },
+
+            Element::Solid(data, ElementType::Lamp(glow)) => {
+                let glow = lamp_update(glow, position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Solid(d, ElementType::Lamp(glow)))
+            },
+
+            Element::Solid(data, ElementType::Heater(heat)) => {
+                let heat = heater_update(heat, position, field_access);
+                data.update(&properties, position, field_access, |d,_,_| Element::Solid(d, ElementType::Heater(heat)))
+            },
 
             Element::MovableSolid(d, t) => 
-                d.update(position, field_access, |d,_,_| Element::MovableSolid(d, t)), 
+                d.update(&properties, position, field_access, |d,_,_| Element::MovableSolid(d, t)), 
             Element::Solid(d, t) => 
-                d.update(position, field_access, |d,_,_| Element::Solid(d, t)), 
+                d.update(&properties, position, field_access, |d,_,_| Element::Solid(d, t)), 
             Element::Liquid(d, t) => 
-                d.update(position, field_access, |d,_,_| Element::Liquid(d, t)), 
+                d.update(&properties, position, field_access, |d,_,_| Element::Liquid(d, t)), 
         }
     }
 
     pub fn refresh(self) -> Element{
+        let properties = self.properties();
         match self {
-            Element::MovableSolid(d, t) => Element::MovableSolid(d.refresh(), t),
-            Element::Liquid(d, t) => Element::Liquid(d.refresh(), t),
-            Element::Solid(d, t) => Element::Solid(d.refresh(), t),
+            Element::MovableSolid(d, t) => Element::MovableSolid(d.refresh(&properties), t),
+            Element::Liquid(d, t) => Element::Liquid(d.refresh(&properties), t),
+            Element::Solid(d, t) => Element::Solid(d.refresh(&properties), t),
         }
     }
 
+    // alcohol gets lighter the more concentrated it is
     pub fn density(&self) -> f64{
+        match self.get_type() {
+            ElementType::Alcohol(concentration) => 7. - 2. * concentration as f64 / PURE_ALCOHOL as f64,
+            t => t.material().properties().density,
+        }
+    }
+
+    pub fn properties(&self) -> MaterialProperties{
+        MaterialProperties { density: self.density(), ..*self.get_type().material().properties() }
+    }
+
+    pub fn same_as(&self, other: &Element) -> bool{
+        Cell::from(*self) == Cell::from(*other)
+    }
+
+    pub fn stable_time(&self) -> Option<isize>{
         match self {
-            Element::MovableSolid(d, _) => d.density(),
-            Element::Liquid(d, _) => d.density(),
-            Element::Solid(d, _) => d.density(),
+            Element::MovableSolid(d, _) => Some(d.stable_time),
+            Element::Liquid(d, _) => Some(d.stable_time),
+            Element::Solid(_, _) => None,
         }
     }
 
@@ -2,105 +2,133 @@ use std::cmp;
 
 use rand::{thread_rng, Rng};
 
-use crate::field::chunk_context::ChunkContext;
+use crate::field::{chunk_context::ChunkContext, gravity::step};
 
-use super::{Element, get_avalible_point, ElementData};
+use super::{Element, ElementData, material::MaterialProperties};
+
+pub const MAX_MOMENTUM: isize = 3;
 
 #[derive(Clone, Copy)]
 pub struct MovableSolid{
     pub is_falling: bool, 
     pub stable_time: isize,
-    pub flow_coefficient: f32,
-    pub move_time: isize,
-    pub keep_alive_extra_time: Option<isize>,
-    pub unstuck_speed: isize,
-    pub disperse_distance: isize,
-    pub density: f64,
-    pub slip_through_prob: f64,
+    // signed along the side axis of gravity
+    pub momentum: isize,
 }
 
-impl PartialEq for MovableSolid {
-    fn eq(&self, other: &Self) -> bool {
-        self.flow_coefficient == other.flow_coefficient && self.move_time == other.move_time && self.unstuck_speed == other.unstuck_speed && self.disperse_distance == other.disperse_distance && self.density == other.density && self.slip_through_prob == other.slip_through_prob
-    }
+impl MovableSolid {
+    pub fn new() -> MovableSolid {MovableSolid { is_falling: true, stable_time: 0, momentum: 0 }}
+    pub fn set_falling(mut self, value: bool) -> MovableSolid {self.is_falling = value; self}
+    pub fn set_stable_time(mut self, value: isize) -> MovableSolid {self.stable_time = value; self}
+    pub fn set_momentum(mut self, value: isize) -> MovableSolid {self.momentum = value; self}
+}
 
-    fn ne(&self, other: &Self) -> bool {
-        !self.eq(other)
+impl Default for MovableSolid {
+    fn default() -> Self {
+        MovableSolid::new()
     }
 }
 
-impl MovableSolid {
-    pub fn set_falling(mut self, value: bool) -> MovableSolid {self.is_falling = value; self}
-    pub fn set_stable_time(mut self, value: isize) -> MovableSolid {self.stable_time = value; self}
+// the cell past the first edge towards a column that is steeper than `steepness` from here
+fn slide_destination(position: (isize, isize), side: isize, steepness: f64, max_run: isize,
+    field_access: &ChunkContext, open: impl Fn(Element) -> bool) -> Option<(isize, isize)>{
+    let gravity = field_access.gravity(position);
+    let (down, side_axis) = (gravity.down(), gravity.side());
+    let mut destination = None;
+    for run in 1..=max_run{
+        let cell = step(position, side_axis, side * run);
+        if !field_access.reachable_empty_or_fitting(cell, &open){
+            return None;
+        }
+        let limit = run as f64 * steepness + 1.;
+        let mut drop = 0;
+        while (drop as f64) < limit && field_access.reachable_empty_or_fitting(step(cell, down, drop + 1), &open){
+            drop += 1;
+        }
+        if drop > 0 && destination.is_none(){
+            destination = Some(step(cell, down, 1));
+        }
+        if (drop + 1) as f64 > limit{
+            return destination;
+        }
+    }
+    None
 }
 
+fn rub(momentum: isize, friction: f64) -> isize{
+    let momentum = cmp::min(momentum, MAX_MOMENTUM);
+    if momentum > 1 && thread_rng().gen_bool(friction) {momentum - 1} else {momentum}
+}
 
 impl ElementData for MovableSolid {
     type Item = MovableSolid;
 
-    fn update(mut self, position: (isize, isize), field_access: &mut ChunkContext, convert_func: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element ){
-        let bellow_cord = (position.0, position.1 + 1);
+    fn update(mut self, properties: &MaterialProperties, position: (isize, isize), field_access: &mut ChunkContext, convert_func: impl Fn(Self::Item, (isize, isize), &ChunkContext) -> Element ){
+        let gravity = field_access.gravity(position);
+        let (down, side_axis) = (gravity.down(), gravity.side());
+        let bellow_cord = step(position, down, 1);
+
+        if let Some(target) = field_access.gust(position, properties.density){
+            field_access.move_from_to(position, target, convert_func(self.set_stable_time(0).set_momentum(0), target, field_access));
+            return;
+        }
 
         let move_function = |e: Element| {
             if e.solid().is_some(){
                 return false;
             }
-            let prob = thread_rng().gen_bool(f64::max(1. - e.density() / self.density, self.slip_through_prob));
-            if let Some(data) = e.movable_solid(){
-                return self != *data && prob;
+            let prob = thread_rng().gen_bool(f64::max(1. - e.density() / properties.density, properties.slip_through_prob));
+            if e.movable_solid().is_some(){
+                return e.properties() != *properties && prob;
             }
             prob
         };
 
-        if field_access.reachable_empty_or_fitting(bellow_cord, move_function){
+        if !gravity.is_zero() && field_access.reachable_empty_or_fitting(bellow_cord, move_function){
             field_access.move_from_to(position, bellow_cord, convert_func(self
                 .set_stable_time(0)
                 .set_falling(false), bellow_cord, field_access));
             return;
         }
 
-        let chance = thread_rng().gen_range(
-            cmp::min(0, (self.flow_coefficient * self.move_time as f32) as isize)..=
-            ((self.flow_coefficient * self.move_time as f32) as isize));
-
-        if chance >= self.stable_time {
-            let side: isize = if rand::random() {1} else {-1}; 
-            let shift = position.0 + side;
-            let mut do_move = false;
-            let mut destination = position.0 + side * self.disperse_distance;
-            let adjesent = (shift, position.1);
-
-            if field_access.reachable_empty_or_fitting(adjesent, move_function) {
-                do_move = true;
-            }
-            else{
-                let shift = position.0 - side;
-
-                let adjesent = (shift, position.1);
-
-                if field_access.reachable_empty_or_fitting(adjesent, move_function) {
-                    do_move = true;
-                    destination = position.0 - side * self.disperse_distance;
-                }
+        let open = |e: Element| e.liquid().is_some() && e.density() < properties.density;
+
+        // a momentum of one is only the heading
+        if !gravity.is_zero() && self.momentum.abs() > 1 {
+            let side = self.momentum.signum();
+            let ahead = step(position, side_axis, side);
+            let below_ahead = step(ahead, down, 1);
+            if field_access.reachable_empty_or_fitting(ahead, open){
+                let (destination, momentum) = if field_access.reachable_empty_or_fitting(below_ahead, open) {
+                    (below_ahead, self.momentum.abs() + 1)
+                } else {
+                    (ahead, self.momentum.abs() - 1)
+                };
+                let momentum = side * rub(momentum, properties.friction);
+                field_access.move_from_to(position, destination, convert_func(self
+                    .set_stable_time(0)
+                    .set_momentum(momentum), destination, field_access));
+                return;
             }
+            self.momentum = 0;
+        }
 
-            if do_move {
-                let destination = get_avalible_point(position, 
-                    (destination, position.1), 
-                    field_access, |e| {e.is_none() || move_function(e.unwrap())});
-                let destination = (destination.0, destination.1 + 1);
-                if field_access.reachable_empty_or_fitting(destination, |e| {
-                    e.density() < self.density || move_function(e)
-                }){
-                    field_access.move_from_to(position, destination, 
-                        convert_func(self
-                            .set_stable_time(0), destination, field_access));
+        if !gravity.is_zero(){
+            let steepness = (properties.repose_angle as f64).to_radians().tan();
+            let first: isize = if self.momentum != 0 {self.momentum.signum()} else if rand::random() {1} else {-1};
+            for side in [first, -first]{
+                if let Some(destination) = slide_destination(position, side, steepness, properties.disperse_distance, field_access, open){
+                    let momentum = if side == self.momentum.signum() {self.momentum.abs() + 1} else {1};
+                    field_access.move_from_to(position, destination, convert_func(self
+                        .set_stable_time(0)
+                        .set_momentum(side * rub(momentum, properties.friction)), destination, field_access));
+                    return;
                 }
-                return;
             }
         }
+        self.momentum = 0;
 
-        if self.stable_time < self.keep_alive_extra_time.or(Some(self.move_time)).unwrap(){
+        if self.stable_time < properties.keep_alive_extra_time.or(Some(properties.move_time)).unwrap(){
             field_access.keep_alive(position);
         }
 
@@ -109,12 +137,8 @@ impl ElementData for MovableSolid {
             
     }
 
-    fn refresh(self) -> Self::Item{
-        self.set_stable_time(cmp::max(0, self.stable_time - self.unstuck_speed))
-    }
-
-    fn density(&self) -> f64 {
-        self.density
+    fn refresh(self, properties: &MaterialProperties) -> Self::Item{
+        self.set_stable_time(cmp::max(0, self.stable_time - properties.unstuck_speed))
     }
 
 }
\ No newline at end of file
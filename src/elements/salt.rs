@@ -0,0 +1,25 @@
+use rand::{thread_rng, Rng};
+
+use crate::field::{chunk_context::ChunkContext, neighbours::Neighbours};
+
+use super::{movable_solids::MovableSolid, Element, ElementData, ElementType, material::MaterialProperties};
+
+const DISSOLVE_CHANCE: f64 = 0.05;
+
+pub fn salt_update(data: MovableSolid, properties: &MaterialProperties, position: (isize, isize), field_access: &mut ChunkContext){
+    let mut touches_water = false;
+    for n in Neighbours::direct_of(position){
+        if let Ok(Some(Element::Liquid(_, ElementType::Water(_)))) = field_access.get(n){
+            touches_water = true;
+            if thread_rng().gen_bool(DISSOLVE_CHANCE){
+                field_access.clear(position);
+                field_access.set(n, Element::salt_water());
+                return;
+            }
+        }
+    }
+    if touches_water{
+        field_access.keep_alive(position);
+    }
+    data.update(properties, position, field_access, |d,_,_| Element::MovableSolid(d, ElementType::Salt));
+}
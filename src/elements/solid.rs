@@ -1,24 +1,17 @@
-use super::ElementData;
+use super::{ElementData, material::MaterialProperties};
 
 
 #[derive(Clone, Copy)]
-pub struct Solid{
-    pub density: f64
-}
+pub struct Solid{}
 
 impl ElementData for Solid{
     type Item = Solid;
 
-    fn update(self, position: (isize, isize), field_access: &mut crate::field::chunk_context::ChunkContext, to_element: impl Fn(Self::Item, (isize, isize), &crate::field::chunk_context::ChunkContext) -> super::Element) {
+    fn update(self, _: &MaterialProperties, position: (isize, isize), field_access: &mut crate::field::chunk_context::ChunkContext, to_element: impl Fn(Self::Item, (isize, isize), &crate::field::chunk_context::ChunkContext) -> super::Element) {
         field_access.set_static(position, to_element(self, position, field_access))
     }
 
-    fn refresh(self) -> Self::Item {
+    fn refresh(self, _: &MaterialProperties) -> Self::Item {
         self
     }
-
-    fn density(&self) -> f64 {
-        self.density
-    }
 }
-
@@ -1,87 +1,144 @@
-use crate::elements::Element;
+use std::{sync::OnceLock, mem};
 
-use super::{rect::{Rect, RectIterator}, neighbours::Neighbours, CHUNK_ISIZE};
+use crate::elements::{Element, cell::Cell};
 
-pub const CHUNK_SIZE: (usize, usize) = (32, 32);
+use super::{rect::{Rect, RectIterator}, neighbours::Neighbours, dirty::DirtyMask};
+
+pub const DEFAULT_CHUNK_SIZE: (usize, usize) = (32, 32);
+
+static CHUNK_SIZE: OnceLock<(usize, usize)> = OnceLock::new();
+
+// fixed once per process, returns false if it was already set
+pub fn set_chunk_size(size: (usize, usize)) -> bool{
+    CHUNK_SIZE.set(size).is_ok()
+}
+
+pub fn chunk_size() -> (usize, usize){
+    *CHUNK_SIZE.get_or_init(|| DEFAULT_CHUNK_SIZE)
+}
 
 pub type CordInChunk = (usize, usize);
 
+// tick a cell was last written, wide enough to never wrap around
+pub type UpdateStamp = u64;
+
+pub const NEVER_UPDATED: UpdateStamp = 0;
+
 pub struct Chunk{
-    field: Vec<Vec<Option<Element>>>,
-    field_parity: Vec<Vec<bool>>,
-    current_update_area: Rect,
-    next_rect: Rect,
+    width: usize,
+    height: usize,
+    field: Vec<Option<Cell>>,
+    stamps: Vec<UpdateStamp>,
+    current_dirty: DirtyMask,
+    next_dirty: DirtyMask,
     elements_count: usize,
 }
 
 impl Chunk {
-    pub fn new(parity: bool) -> Chunk{
-        let field = vec![vec![None; CHUNK_SIZE.0]; CHUNK_SIZE.1];
-        let field_parity = vec![vec![parity; CHUNK_SIZE.0]; CHUNK_SIZE.1];
-        Chunk { field, current_update_area: Rect::new(), next_rect: Rect::new(), elements_count: 0, field_parity }
+    pub fn new() -> Chunk{
+        let size = chunk_size();
+        let field = vec![None; size.0 * size.1];
+        let stamps = vec![NEVER_UPDATED; size.0 * size.1];
+        Chunk { width: size.0, height: size.1, field, current_dirty: DirtyMask::new(size), next_dirty: DirtyMask::new(size), elements_count: 0, stamps }
+    }
+
+    pub fn size(&self) -> (isize, isize){
+        (self.width as isize, self.height as isize)
+    }
+
+    fn index(&self, position: CordInChunk) -> usize{
+        position.1 * self.width + position.0
     }
 
     fn set_value(&mut self, position: CordInChunk, element: Option<Element>) {
-        self.field[position.1][position.0] = element;
+        let index = self.index(position);
+        self.field[index] = element.map(Cell::from);
     }
 
     pub fn needs_updates(&self) -> bool{
-        self.current_update_area.has_value()
+        !self.current_dirty.is_empty()
+    }
+
+    pub fn is_dirty(&self, position: CordInChunk) -> bool{
+        self.current_dirty.contains(position)
+    }
+
+    pub fn row_is_dirty(&self, y: usize) -> bool{
+        !self.current_dirty.row_is_empty(y)
+    }
+
+    pub fn dirty_cells(&self) -> usize{
+        self.current_dirty.count()
+    }
+
+    fn is_empty(&self, position: CordInChunk) -> bool{
+        self.field[self.index(position)].is_none()
     }
 
     pub fn get(&self, position: CordInChunk) -> Option<Element>{
-        self.field[position.1][position.0]
+        self.field[self.index(position)].map(Cell::element)
     }
 
-    pub fn parity(&self, position: CordInChunk) -> bool {
-        self.field_parity[position.1][position.0]
+    pub fn stamp(&self, position: CordInChunk) -> UpdateStamp {
+        self.stamps[self.index(position)]
     }
 
-    pub fn set_parity(&mut self, position: CordInChunk, parity: bool) {
-        self.field_parity[position.1][position.0] = parity;
+    pub fn set_stamp(&mut self, position: CordInChunk, stamp: UpdateStamp) {
+        let index = self.index(position);
+        self.stamps[index] = stamp;
     }
 
-    pub fn set(&mut self, position: CordInChunk, element: Element, parity: bool){
-        if self.get(position).is_none(){
+    pub fn set(&mut self, position: CordInChunk, element: Element, stamp: UpdateStamp){
+        if self.is_empty(position){
             self.elements_count += 1;
-            self.next_rect = self.next_rect.expand(position);
+            self.next_dirty.insert(position);
         }
-        self.set_parity(position, parity);
+        self.set_stamp(position, stamp);
         self.set_value(position, Some(element));
     }
 
     pub fn clear(&mut self, position: CordInChunk){
-        if self.get(position).is_some(){
+        if !self.is_empty(position){
             self.elements_count -= 1;
         }
         self.set_value(position, None);
     }
 
     pub fn add_point_in_update_cycle_with_neighbourhood(&mut self, position: CordInChunk){
-        self.next_rect = self.next_rect.expand(position);
-        for n in Neighbours::direct_of((position.0 as isize, position.1 as isize)).with_boundaries(Rect::from((0, 0), CHUNK_ISIZE)){
-            self.next_rect = self.next_rect.expand((n.0 as usize, n.1 as usize));
+        self.add_point_in_update_cycle(position);
+        for n in Neighbours::direct_of((position.0 as isize, position.1 as isize)).with_boundaries(Rect::from((0, 0), self.size())){
+            self.add_point_in_update_cycle((n.0 as usize, n.1 as usize));
         }
     }
 
     pub fn add_point_in_update_cycle(&mut self, position: CordInChunk){
-        self.next_rect = self.next_rect.expand(position);
+        self.next_dirty.insert(position);
+    }
+
+    pub fn kept_alive(&self, position: CordInChunk) -> bool{
+        self.next_dirty.contains(position)
     }
 
     pub fn update_rect(&mut self){
-        self.current_update_area = self.next_rect;
-        self.next_rect = Rect::new();
+        mem::swap(&mut self.current_dirty, &mut self.next_dirty);
+        self.next_dirty.clear();
     }
 
     pub fn into_iter(&self) -> RectIterator<usize>{
-        self.current_update_area.into_iter()
+        self.current_dirty.bounds().into_iter()
     }
 
     pub fn get_update_rect(&self) -> Rect{
-        self.current_update_area
+        self.current_dirty.bounds()
     }
 
     pub fn number_of_elements(&self) -> usize{
         self.elements_count
     }
+}
+
+impl Default for Chunk {
+    fn default() -> Self {
+        Chunk::new()
+    }
 }
\ No newline at end of file
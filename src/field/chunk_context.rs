@@ -1,82 +1,140 @@
-use std::{cell::RefCell, collections::HashMap, rc::Rc, ops::Deref, sync::{Mutex, MutexGuard, Arc, RwLock}, hash::Hash};
+use std::{cell::RefCell, rc::Rc, ops::Deref, sync::{Mutex, MutexGuard, Arc, RwLock}, hash::Hash};
 
 use crate::elements::{Element, ElementData};
 
-use super::{chunk::{Chunk, self, CordInChunk}, ChunkCord, global_cord_to_chunk_local, neighbours::Neighbours, ChunkRef};
+use super::{chunk::{Chunk, self, CordInChunk, UpdateStamp}, ChunkCord, global_cord_to_chunk_local, neighbours::Neighbours, ChunkRef, gravity::{GravityMap, Gravity, step}, wind::WindMap};
 
+// writes outside of the worker's reach, replayed by the field after all passes
 #[derive(Clone, Copy)]
 pub enum UnsolvedActions{
     MissingChunkInsertion{
         chunk_cord: ChunkCord,
         insertion_cord: CordInChunk,
         elementToInsert: Element
+    },
+    Set{
+        position: (isize, isize),
+        element: Element,
+    },
+    // `original` is what sat at `from` when the move was deferred
+    Move{
+        from: (isize, isize),
+        to: (isize, isize),
+        element: Element,
+        original: Element,
+    },
+    Clear{
+        position: (isize, isize),
+    },
+    KeepAlive{
+        position: (isize, isize),
+    },
+    WakeUp{
+        position: (isize, isize),
+    },
+}
+
+enum ChunkAccess<'a>{
+    Current,
+    Neighbour(&'a ChunkRef),
+    Missing,
+    OutOfRange,
+    Wall,
+    Void,
+}
+
+
+// across a wrapping edge this is the chunk on the other side of the world
+#[derive(Clone, Default)]
+pub enum NeighbourChunk{
+    Chunk(ChunkRef),
+    Missing,
+    #[default]
+    Wall,
+    Void,
+}
+
+pub type ChunkNeighbourhood = [NeighbourChunk; 9];
+
+pub fn neighbourhood_index(center: ChunkCord, cord: ChunkCord) -> Option<usize>{
+    let offset = (cord.0 - center.0, cord.1 - center.1);
+    if offset.0.abs() > 1 || offset.1.abs() > 1{
+        return None;
     }
+    Some(((offset.1 + 1) * 3 + offset.0 + 1) as usize)
 }
 
+// the cell lies behind a wall or outside of the chunks a worker can reach
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Unreachable;
 
-pub struct ChunkContext{
-    current_chunk: ChunkRef,
+// only cells in neighbouring chunks go through their locks
+pub struct ChunkContext<'a>{
+    current_chunk: &'a mut Chunk,
     current_chunk_cord: ChunkCord, 
-    neighbours: HashMap<ChunkCord, Option<ChunkRef>>,
+    neighbours: &'a ChunkNeighbourhood,
+    gravity: &'a GravityMap,
+    wind: &'a WindMap,
     pub unsolved_actions: Vec<UnsolvedActions>,
     pub updated_coordinates: Vec<(isize, isize)>,
-    parity: bool
+    pub voided: usize,
+    stamp: UpdateStamp,
+    chunk_size: (isize, isize),
 }
 
-impl ChunkContext {
+impl<'a> ChunkContext<'a> {
 
-    pub fn new(current_chunk: ChunkRef,
+    pub fn new(current_chunk: &'a mut Chunk,
     current_chunk_cord: ChunkCord,
-    neighbours: HashMap<ChunkCord, Option<ChunkRef>>, parity: bool) -> ChunkContext{
-        ChunkContext { current_chunk, current_chunk_cord, neighbours, 
-            unsolved_actions: Vec::new(), parity, updated_coordinates: Vec::new() }
+    neighbours: &'a ChunkNeighbourhood, gravity: &'a GravityMap, wind: &'a WindMap, stamp: UpdateStamp) -> ChunkContext<'a>{
+        let chunk_size = current_chunk.size();
+        ChunkContext { current_chunk, current_chunk_cord, neighbours, gravity, wind, 
+            unsolved_actions: Vec::new(), stamp, updated_coordinates: Vec::new(), voided: 0, chunk_size }
     }
 
-    fn is_in_neighbour_range(&self, cord: ChunkCord) -> bool{
-        (cord.0 - self.current_chunk_cord.0).abs() <= 1 && (cord.1 - self.current_chunk_cord.1).abs() <= 1
+    fn access(&self, cord: ChunkCord) -> ChunkAccess<'a>{
+        if self.current_chunk_cord == cord{
+            return ChunkAccess::Current;
+        }
+        let index = match neighbourhood_index(self.current_chunk_cord, cord) {
+            Some(index) => index,
+            None => return ChunkAccess::OutOfRange,
+        };
+        match &self.neighbours[index] {
+            NeighbourChunk::Chunk(chunk) => ChunkAccess::Neighbour(chunk),
+            NeighbourChunk::Missing => ChunkAccess::Missing,
+            NeighbourChunk::Wall => ChunkAccess::Wall,
+            NeighbourChunk::Void => ChunkAccess::Void,
+        }
     }
 
-    pub fn current_chunk(&self) -> &ChunkRef{
-        &self.current_chunk
+    pub fn chunk_size(&self) -> (isize, isize){
+        self.chunk_size
     }
 
-    pub fn get(&self, position: (isize, isize)) -> Result<Option<Element>, ()>{
-        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position);
-        if !self.is_in_neighbour_range(chunk_c){
-            return Err(());
-        }
-        if self.current_chunk_cord == chunk_c{
-            return Ok(self.current_chunk.read().unwrap().get(in_chunk_c));
+    pub fn current_chunk(&self) -> &Chunk{
+        self.current_chunk
+    }
+
+    pub fn get(&self, position: (isize, isize)) -> Result<Option<Element>, Unreachable>{
+        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position, self.chunk_size);
+        match self.access(chunk_c) {
+            ChunkAccess::Current => Ok(self.current_chunk.get(in_chunk_c)),
+            ChunkAccess::Neighbour(chunk) => Ok(chunk.read().unwrap().get(in_chunk_c)),
+            ChunkAccess::Missing | ChunkAccess::Void => Ok(None),
+            ChunkAccess::OutOfRange | ChunkAccess::Wall => Err(Unreachable),
         }
-        let chunk_access = self.neighbours.get(&chunk_c);
-        if let Some(chunk_option) = chunk_access{
-            if let Some(chunk) = chunk_option{
-                return Ok(chunk.read().unwrap().get(in_chunk_c));
-            }
-            return Ok(None);
-        } 
-        Err(())
     }
 
     fn keep_adjesent_cells_alive(&mut self, position: (isize, isize)){
         for neighbour in Neighbours::of(position){
-            let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(neighbour);
-
-            if self.current_chunk_cord == chunk_c{
-                let mut lock = self.current_chunk.write().unwrap();
-                lock.add_point_in_update_cycle(in_chunk_c);
-                if let Some(element) = lock.get(in_chunk_c){
-                    let parity = lock.parity(in_chunk_c);
-                    lock.set(in_chunk_c, element.refresh(), parity);
-                }
-            }
-            else if let Some(Some(chunk)) = self.neighbours.get(&chunk_c){
-                let mut lock = chunk.write().unwrap();
-                lock.add_point_in_update_cycle(in_chunk_c);
-                if let Some(element) = lock.get(in_chunk_c){
-                    let parity = lock.parity(in_chunk_c);
-                    lock.set(in_chunk_c, element.refresh(), parity);
-                }
+            let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(neighbour, self.chunk_size);
+            match self.access(chunk_c) {
+                ChunkAccess::Current => wake_up(self.current_chunk, in_chunk_c),
+                ChunkAccess::Neighbour(chunk) => wake_up(&mut chunk.write().unwrap(), in_chunk_c),
+                ChunkAccess::Missing | ChunkAccess::OutOfRange =>
+                    self.unsolved_actions.push(UnsolvedActions::WakeUp { position: neighbour }),
+                ChunkAccess::Wall | ChunkAccess::Void => {},
             }
         }
     }
@@ -109,35 +167,30 @@ impl ChunkContext {
     }
 
     pub fn keep_alive_local(&mut self, position: CordInChunk){
-        self.current_chunk.write().unwrap().add_point_in_update_cycle(position);
+        self.current_chunk.add_point_in_update_cycle(position);
     }
 
     pub fn keep_alive(&mut self, position: (isize, isize)){
-        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position);
-        if !self.is_in_neighbour_range(chunk_c){
-            return;
-        }
-        if self.current_chunk_cord == chunk_c{
-            self.keep_alive_local(in_chunk_c);
-        }
-        else if let Some(Some(chunk)) = self.neighbours.get(&chunk_c){
-            let mut lock = chunk.write().unwrap();
-            lock.add_point_in_update_cycle(in_chunk_c);
+        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position, self.chunk_size);
+        match self.access(chunk_c) {
+            ChunkAccess::Current => self.keep_alive_local(in_chunk_c),
+            ChunkAccess::Neighbour(chunk) => chunk.write().unwrap().add_point_in_update_cycle(in_chunk_c),
+            ChunkAccess::Missing | ChunkAccess::OutOfRange =>
+                self.unsolved_actions.push(UnsolvedActions::KeepAlive { position }),
+            ChunkAccess::Wall | ChunkAccess::Void => {},
         }
     }
 
     pub fn clear(&mut self, position: (isize, isize)){
-        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position);
-        if !self.is_in_neighbour_range(chunk_c){
-            return;
-        }
-
-        if self.current_chunk_cord == chunk_c{
-            self.current_chunk.write().unwrap().clear(in_chunk_c);
-        }
-        else if let Some(Some(chunk)) = self.neighbours.get(&chunk_c){
-            let mut lock = chunk.write().unwrap();
-            lock.clear(in_chunk_c);
+        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position, self.chunk_size);
+        match self.access(chunk_c) {
+            ChunkAccess::Current => self.current_chunk.clear(in_chunk_c),
+            ChunkAccess::Neighbour(chunk) => chunk.write().unwrap().clear(in_chunk_c),
+            ChunkAccess::Missing | ChunkAccess::OutOfRange => {
+                self.unsolved_actions.push(UnsolvedActions::Clear { position });
+                return;
+            },
+            ChunkAccess::Wall | ChunkAccess::Void => return,
         }
         self.updated_coordinates.push(position);
         self.keep_adjesent_cells_alive(position);
@@ -152,7 +205,17 @@ impl ChunkContext {
     }
 
     pub fn move_from_to(&mut self, from: (isize, isize), to: (isize, isize), element: Element){
-        let other = self.get(to).unwrap();
+        let (chunk_c, _) = global_cord_to_chunk_local(to, self.chunk_size);
+        let other = match self.access(chunk_c) {
+            ChunkAccess::Missing | ChunkAccess::OutOfRange => {
+                if let Ok(Some(original)) = self.get(from){
+                    self.unsolved_actions.push(UnsolvedActions::Move { from, to, element, original });
+                }
+                return;
+            },
+            ChunkAccess::Wall => return,
+            _ => self.get(to).unwrap(),
+        };
         self.set(to, element);
         match other {
             Some(element) => self.set(from, element),
@@ -161,28 +224,31 @@ impl ChunkContext {
     }
 
     fn set_internal(&mut self, position: (isize, isize), element: Element, keep_adjesent_alive: bool){
-        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position);
-        if !self.is_in_neighbour_range(chunk_c){
-            return;
-        }
-        {
-            if self.current_chunk_cord == chunk_c{
-                self.current_chunk.write().unwrap().set(in_chunk_c, element, !self.parity);
-            }
-            else if let Some(Some(chunk)) = self.neighbours.get(&chunk_c){
-                let mut lock = chunk.write().unwrap();
-                lock.set(in_chunk_c, element, !self.parity);
-            }
-            else {
-                if let Some(chunk) = self.neighbours.get(&chunk_c){
-                    self.unsolved_actions.push(UnsolvedActions::MissingChunkInsertion 
-                        { chunk_cord: chunk_c, insertion_cord: in_chunk_c, elementToInsert: element });
+        let (chunk_c, in_chunk_c) = global_cord_to_chunk_local(position, self.chunk_size);
+        match self.access(chunk_c) {
+            ChunkAccess::Current => self.current_chunk.set(in_chunk_c, element, self.stamp),
+            ChunkAccess::Neighbour(chunk) => chunk.write().unwrap().set(in_chunk_c, element, self.stamp),
+            ChunkAccess::Missing => {
+                self.unsolved_actions.push(UnsolvedActions::MissingChunkInsertion 
+                    { chunk_cord: chunk_c, insertion_cord: in_chunk_c, elementToInsert: element });
+                if keep_adjesent_alive{
+                    self.keep_adjesent_cells_alive(position);
                 }
                 return;
-            }
+            },
+            ChunkAccess::OutOfRange => {
+                self.unsolved_actions.push(UnsolvedActions::Set { position, element });
+                return;
+            },
+            ChunkAccess::Void => {
+                self.voided += 1;
+                return;
+            },
+            ChunkAccess::Wall => return,
         }
         self.updated_coordinates.push(position);
         if keep_adjesent_alive{
+            self.keep_alive(position);
             self.keep_adjesent_cells_alive(position);
         }
     }
@@ -191,7 +257,36 @@ impl ChunkContext {
         self.current_chunk_cord
     }
 
+    pub fn gravity(&self, position: (isize, isize)) -> Gravity {
+        self.gravity.at(position)
+    }
+
+    // a blocked cell gets lifted against gravity, which piles up waves and dunes
+    pub fn gust(&mut self, position: (isize, isize), density: f64) -> Option<(isize, isize)> {
+        if self.wind.at(position) == (0., 0.){
+            return None;
+        }
+        self.keep_alive(position);
+        let direction = self.wind.gust(position, density)?;
+        let target = step(position, direction, 1);
+        let lifted = step(target, self.gravity(position).down(), -1);
+        [target, lifted].into_iter().find(|cord| *cord != position && self.empty_and_reachable(*cord))
+    }
+
+    pub fn stamp(&self) -> UpdateStamp {
+        self.stamp
+    }
+
+    // scan direction flips every tick
     pub fn parity(&self) -> bool {
-        self.parity
+        self.stamp % 2 == 1
     }
-}
\ No newline at end of file
+}
+
+pub fn wake_up(chunk: &mut Chunk, position: CordInChunk){
+    chunk.add_point_in_update_cycle(position);
+    if let Some(element) = chunk.get(position){
+        let stamp = chunk.stamp(position);
+        chunk.set(position, element.refresh(), stamp);
+    }
+}
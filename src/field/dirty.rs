@@ -0,0 +1,100 @@
+use super::{chunk::CordInChunk, rect::Rect};
+
+const WORD_BITS: usize = 64;
+
+// one bit per cell, row by row, plus the rect bounding the set bits
+#[derive(Clone)]
+pub struct DirtyMask{
+    words_per_row: usize,
+    words: Vec<u64>,
+    bounds: Rect,
+}
+
+impl DirtyMask {
+    pub fn new(size: (usize, usize)) -> DirtyMask{
+        let words_per_row = size.0.div_ceil(WORD_BITS);
+        DirtyMask { words_per_row, words: vec![0; words_per_row * size.1], bounds: Rect::new() }
+    }
+
+    fn word(&self, position: CordInChunk) -> (usize, u64){
+        (position.1 * self.words_per_row + position.0 / WORD_BITS, 1 << (position.0 % WORD_BITS))
+    }
+
+    fn row(&self, y: usize) -> &[u64]{
+        &self.words[y * self.words_per_row..(y + 1) * self.words_per_row]
+    }
+
+    pub fn insert(&mut self, position: CordInChunk){
+        let (index, bit) = self.word(position);
+        self.words[index] |= bit;
+        self.bounds = self.bounds.expand(position);
+    }
+
+    pub fn contains(&self, position: CordInChunk) -> bool{
+        let (index, bit) = self.word(position);
+        self.words[index] & bit != 0
+    }
+
+    pub fn row_is_empty(&self, y: usize) -> bool{
+        self.row(y).iter().all(|word| *word == 0)
+    }
+
+    pub fn is_empty(&self) -> bool{
+        !self.bounds.has_value()
+    }
+
+    pub fn bounds(&self) -> Rect{
+        self.bounds
+    }
+
+    pub fn count(&self) -> usize{
+        self.words.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    pub fn clear(&mut self){
+        let rows = self.bounds.top() * self.words_per_row..self.bounds.bottom() * self.words_per_row;
+        self.words[rows].fill(0);
+        self.bounds = Rect::new();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn inserts_merge_into_one_bounding_rect(){
+        let mut mask = DirtyMask::new((100, 10));
+        assert!(mask.is_empty());
+        mask.insert((70, 2));
+        mask.insert((3, 5));
+        mask.insert((70, 2));
+        let bounds = mask.bounds();
+        assert_eq!((bounds.left(), bounds.top(), bounds.right(), bounds.bottom()), (3, 2, 71, 6));
+        assert_eq!(mask.count(), 2);
+    }
+
+    #[test]
+    fn cells_past_the_first_word_stay_apart(){
+        let mut mask = DirtyMask::new((100, 2));
+        mask.insert((64, 1));
+        assert!(mask.contains((64, 1)));
+        assert!(!mask.contains((0, 1)));
+        assert!(!mask.contains((64, 0)));
+        assert!(mask.row_is_empty(0));
+        assert!(!mask.row_is_empty(1));
+    }
+
+    #[test]
+    fn clear_empties_the_mask(){
+        let mut mask = DirtyMask::new((32, 32));
+        mask.insert((1, 1));
+        mask.insert((31, 31));
+        mask.clear();
+        assert!(mask.is_empty());
+        assert_eq!(mask.count(), 0);
+        assert!(!mask.contains((31, 31)));
+        mask.insert((5, 5));
+        assert_eq!(mask.count(), 1);
+    }
+}
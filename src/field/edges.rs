@@ -0,0 +1,126 @@
+use super::rect::Rect;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum EdgeMode{
+    Wall,
+    Void,
+    Wrap,
+}
+
+impl EdgeMode {
+    pub fn parse(value: &str) -> Result<EdgeMode, String>{
+        match value.trim() {
+            "wall" => Ok(EdgeMode::Wall),
+            "void" => Ok(EdgeMode::Void),
+            "wrap" => Ok(EdgeMode::Wrap),
+            _ => Err(format!("unknown edge mode '{}', expected wall, void or wrap", value)),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct WorldEdges{
+    pub left: EdgeMode,
+    pub right: EdgeMode,
+    pub top: EdgeMode,
+    pub bottom: EdgeMode,
+}
+
+impl Default for WorldEdges {
+    fn default() -> Self {
+        WorldEdges::all(EdgeMode::Wall)
+    }
+}
+
+impl WorldEdges {
+    pub fn all(mode: EdgeMode) -> WorldEdges{
+        WorldEdges { left: mode, right: mode, top: mode, bottom: mode }
+    }
+
+    // an even number of chunks keeps chunks of one update order apart across the seam
+    pub fn check(&self, chunk_count: (usize, usize)) -> Result<(), String>{
+        let axes = [("left", self.left, "right", self.right, chunk_count.0),
+            ("top", self.top, "bottom", self.bottom, chunk_count.1)];
+        for (first, first_mode, second, second_mode, count) in axes{
+            if (first_mode == EdgeMode::Wrap) != (second_mode == EdgeMode::Wrap){
+                return Err(format!("the {} and {} edges must both wrap", first, second));
+            }
+            if first_mode == EdgeMode::Wrap && !count.is_multiple_of(2){
+                return Err(format!("wrapping between {} and {} needs an even number of chunks, got {}", first, second, count));
+            }
+        }
+        Ok(())
+    }
+
+    // walls win over void in the corners
+    pub fn resolve(&self, world: Rect<isize>, point: (isize, isize)) -> Result<(isize, isize), EdgeMode>{
+        let wrap = |value: isize, low: isize, high: isize| low + (value - low).rem_euclid(high - low);
+        let mut point = point;
+        if self.left == EdgeMode::Wrap{
+            point.0 = wrap(point.0, world.left(), world.right());
+        }
+        if self.top == EdgeMode::Wrap{
+            point.1 = wrap(point.1, world.top(), world.bottom());
+        }
+
+        let crossed = [(point.0 < world.left(), self.left), (point.0 >= world.right(), self.right),
+            (point.1 < world.top(), self.top), (point.1 >= world.bottom(), self.bottom)];
+        if !crossed.iter().any(|(outside, _)| *outside){
+            Ok(point)
+        }
+        else if crossed.iter().any(|(outside, mode)| *outside && *mode == EdgeMode::Wall){
+            Err(EdgeMode::Wall)
+        }
+        else{
+            Err(EdgeMode::Void)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn world() -> Rect<isize>{
+        Rect::from((0, 0), (64, 32))
+    }
+
+    #[test]
+    fn points_inside_are_kept(){
+        for mode in [EdgeMode::Wall, EdgeMode::Void, EdgeMode::Wrap]{
+            assert_eq!(WorldEdges::all(mode).resolve(world(), (10, 20)), Ok((10, 20)));
+        }
+    }
+
+    #[test]
+    fn walls_and_void_stop_points_outside(){
+        assert_eq!(WorldEdges::all(EdgeMode::Wall).resolve(world(), (-1, 5)), Err(EdgeMode::Wall));
+        assert_eq!(WorldEdges::all(EdgeMode::Void).resolve(world(), (5, 32)), Err(EdgeMode::Void));
+    }
+
+    #[test]
+    fn walls_win_over_void_in_the_corners(){
+        let edges = WorldEdges { right: EdgeMode::Void, bottom: EdgeMode::Wall, ..WorldEdges::all(EdgeMode::Void) };
+        assert_eq!(edges.resolve(world(), (64, 32)), Err(EdgeMode::Wall));
+        assert_eq!(edges.resolve(world(), (64, 5)), Err(EdgeMode::Void));
+    }
+
+    #[test]
+    fn wrapping_edges_map_points_back_inside(){
+        let edges = WorldEdges { left: EdgeMode::Wrap, right: EdgeMode::Wrap, ..WorldEdges::default() };
+        assert_eq!(edges.resolve(world(), (-1, 5)), Ok((63, 5)));
+        assert_eq!(edges.resolve(world(), (64 + 3, 5)), Ok((3, 5)));
+        assert_eq!(edges.resolve(world(), (-1, -1)), Err(EdgeMode::Wall));
+        assert_eq!(WorldEdges::all(EdgeMode::Wrap).resolve(world(), (-2, 33)), Ok((62, 1)));
+    }
+
+    #[test]
+    fn wrapping_needs_both_edges_and_an_even_chunk_count(){
+        let horizontal = WorldEdges { left: EdgeMode::Wrap, right: EdgeMode::Wrap, ..WorldEdges::default() };
+        assert!(horizontal.check((4, 3)).is_ok());
+        assert!(horizontal.check((3, 4)).is_err());
+        assert!(WorldEdges { left: EdgeMode::Wrap, ..WorldEdges::default() }.check((4, 4)).is_err());
+        assert!(WorldEdges::all(EdgeMode::Wrap).check((4, 3)).is_err());
+        assert!(WorldEdges::all(EdgeMode::Void).check((3, 3)).is_ok());
+    }
+}
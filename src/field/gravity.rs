@@ -0,0 +1,119 @@
+use super::rect::Rect;
+
+pub const GRAVITY_TILE: isize = 8;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum Gravity{
+    #[default]
+    Down,
+    DownLeft,
+    Left,
+    UpLeft,
+    Up,
+    UpRight,
+    Right,
+    DownRight,
+    Zero,
+}
+
+const GRAVITY_NAMES: [(Gravity, &str); 9] = [(Gravity::Down, "down"), (Gravity::DownLeft, "down-left"), (Gravity::Left, "left"),
+    (Gravity::UpLeft, "up-left"), (Gravity::Up, "up"), (Gravity::UpRight, "up-right"), (Gravity::Right, "right"),
+    (Gravity::DownRight, "down-right"), (Gravity::Zero, "zero")];
+
+impl Gravity {
+    pub fn parse(value: &str) -> Result<Gravity, String>{
+        GRAVITY_NAMES.iter().find(|(_, name)| *name == value.trim()).map(|(gravity, _)| *gravity)
+            .ok_or(format!("unknown gravity '{}', expected one of {}", value,
+                GRAVITY_NAMES.iter().map(|(_, name)| *name).collect::<Vec<_>>().join(", ")))
+    }
+
+    pub fn name(self) -> &'static str{
+        GRAVITY_NAMES.iter().find(|(gravity, _)| *gravity == self).unwrap().1
+    }
+
+    // the next direction clockwise, zero comes after down-right
+    pub fn next(self) -> Gravity{
+        let index = GRAVITY_NAMES.iter().position(|(gravity, _)| *gravity == self).unwrap();
+        GRAVITY_NAMES[(index + 1) % GRAVITY_NAMES.len()].0
+    }
+
+    pub fn is_zero(self) -> bool{
+        self == Gravity::Zero
+    }
+
+    pub fn down(self) -> (isize, isize){
+        match self {
+            Gravity::Down => (0, 1),
+            Gravity::DownLeft => (-1, 1),
+            Gravity::Left => (-1, 0),
+            Gravity::UpLeft => (-1, -1),
+            Gravity::Up => (0, -1),
+            Gravity::UpRight => (1, -1),
+            Gravity::Right => (1, 0),
+            Gravity::DownRight => (1, 1),
+            Gravity::Zero => (0, 0),
+        }
+    }
+
+    // either sign works
+    pub fn side(self) -> (isize, isize){
+        let down = self.down();
+        (-down.1, down.0)
+    }
+}
+
+pub fn step(position: (isize, isize), direction: (isize, isize), distance: isize) -> (isize, isize){
+    (position.0 + direction.0 * distance, position.1 + direction.1 * distance)
+}
+
+// unpainted tiles follow the world gravity
+#[derive(Clone, Default)]
+pub struct GravityMap{
+    world: Gravity,
+    size: (isize, isize),
+    zones: Vec<Option<Gravity>>,
+}
+
+impl GravityMap {
+    pub fn new(world_size: (isize, isize)) -> GravityMap{
+        let size = ((world_size.0 as usize).div_ceil(GRAVITY_TILE as usize) as isize, (world_size.1 as usize).div_ceil(GRAVITY_TILE as usize) as isize);
+        GravityMap { world: Gravity::Down, size, zones: vec![None; (size.0 * size.1) as usize] }
+    }
+
+    fn index(&self, position: (isize, isize)) -> Option<usize>{
+        let tile = (position.0.div_euclid(GRAVITY_TILE), position.1.div_euclid(GRAVITY_TILE));
+        if tile.0 < 0 || tile.1 < 0 || tile.0 >= self.size.0 || tile.1 >= self.size.1{
+            return None;
+        }
+        Some((tile.1 * self.size.0 + tile.0) as usize)
+    }
+
+    pub fn world(&self) -> Gravity{
+        self.world
+    }
+
+    pub fn set_world(&mut self, gravity: Gravity){
+        self.world = gravity;
+    }
+
+    pub fn at(&self, position: (isize, isize)) -> Gravity{
+        self.index(position).and_then(|i| self.zones[i]).unwrap_or(self.world)
+    }
+
+    pub fn paint(&mut self, position: (isize, isize), gravity: Option<Gravity>){
+        if let Some(index) = self.index(position){
+            self.zones[index] = gravity;
+        }
+    }
+
+    pub fn zones(&self) -> Vec<(Rect<isize>, Gravity)>{
+        let mut zones = Vec::new();
+        for (index, zone) in self.zones.iter().enumerate(){
+            if let Some(gravity) = zone{
+                let tile = (index as isize % self.size.0 * GRAVITY_TILE, index as isize / self.size.0 * GRAVITY_TILE);
+                zones.push((Rect::from(tile, (tile.0 + GRAVITY_TILE, tile.1 + GRAVITY_TILE)), *gravity));
+            }
+        }
+        zones
+    }
+}
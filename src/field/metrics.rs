@@ -0,0 +1,99 @@
+use std::{time::Duration, fs::File, io::{BufWriter, Write, self}};
+
+use super::ChunkCord;
+
+#[derive(Clone, Default)]
+pub struct TickMetrics{
+    pub tick: usize,
+    pub total: Duration,
+    pub dispatch: Duration,
+    pub workers: Duration,
+    pub chunk_times: Vec<(ChunkCord, Duration)>,
+    pub unsolved_actions: Duration,
+    pub rigid_bodies: Duration,
+    pub structure: Duration,
+    pub pressure: Duration,
+    pub remove_empty_chunks: Duration,
+    pub cells_processed: usize,
+    pub cells_changed: usize,
+    pub cells_voided: usize,
+    pub chunks: usize,
+}
+
+const CSV_HEADER: &str = "tick,total_us,dispatch_us,workers_us,chunks_updated,chunk_max_us,chunk_sum_us,unsolved_actions_us,rigid_bodies_us,structure_us,pressure_us,remove_empty_chunks_us,cells_processed,cells_changed,cells_voided,chunks";
+
+impl TickMetrics {
+    pub fn chunk_time_max(&self) -> Duration{
+        self.chunk_times.iter().map(|(_, t)| *t).max().unwrap_or(Duration::ZERO)
+    }
+
+    pub fn chunk_time_sum(&self) -> Duration{
+        self.chunk_times.iter().map(|(_, t)| *t).sum()
+    }
+
+    fn to_csv(&self) -> String{
+        format!("{},{},{},{},{},{},{},{},{},{},{},{},{},{},{},{}",
+            self.tick, self.total.as_micros(), self.dispatch.as_micros(), self.workers.as_micros(),
+            self.chunk_times.len(), self.chunk_time_max().as_micros(), self.chunk_time_sum().as_micros(),
+            self.unsolved_actions.as_micros(), self.rigid_bodies.as_micros(), self.structure.as_micros(),
+            self.pressure.as_micros(), self.remove_empty_chunks.as_micros(), self.cells_processed, self.cells_changed,
+            self.cells_voided, self.chunks)
+    }
+
+    fn to_json(&self) -> String{
+        let chunk_times: Vec<String> = self.chunk_times.iter()
+            .map(|(cord, t)| format!("{{\"x\":{},\"y\":{},\"us\":{}}}", cord.0, cord.1, t.as_micros()))
+            .collect();
+        format!("{{\"tick\":{},\"total_us\":{},\"dispatch_us\":{},\"workers_us\":{},\"chunk_times\":[{}],\"unsolved_actions_us\":{},\"rigid_bodies_us\":{},\"structure_us\":{},\"pressure_us\":{},\"remove_empty_chunks_us\":{},\"cells_processed\":{},\"cells_changed\":{},\"cells_voided\":{},\"chunks\":{}}}",
+            self.tick, self.total.as_micros(), self.dispatch.as_micros(), self.workers.as_micros(), chunk_times.join(","),
+            self.unsolved_actions.as_micros(), self.rigid_bodies.as_micros(), self.structure.as_micros(),
+            self.pressure.as_micros(), self.remove_empty_chunks.as_micros(), self.cells_processed, self.cells_changed,
+            self.cells_voided, self.chunks)
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum MetricsFormat{
+    Csv,
+    Json,
+}
+
+// csv for `.csv` files, a json array otherwise
+pub struct MetricsWriter{
+    out: BufWriter<File>,
+    format: MetricsFormat,
+    records: usize,
+}
+
+impl MetricsWriter {
+    pub fn create(path: &str) -> io::Result<MetricsWriter>{
+        let format = if path.ends_with(".csv") {MetricsFormat::Csv} else {MetricsFormat::Json};
+        let mut out = BufWriter::new(File::create(path)?);
+        match format {
+            MetricsFormat::Csv => writeln!(out, "{}", CSV_HEADER)?,
+            MetricsFormat::Json => writeln!(out, "[")?,
+        }
+        Ok(MetricsWriter { out, format, records: 0 })
+    }
+
+    pub fn write(&mut self, metrics: &TickMetrics) -> io::Result<()>{
+        match self.format {
+            MetricsFormat::Csv => writeln!(self.out, "{}", metrics.to_csv())?,
+            MetricsFormat::Json => {
+                if self.records > 0{
+                    writeln!(self.out, ",")?;
+                }
+                write!(self.out, "{}", metrics.to_json())?;
+            },
+        }
+        self.records += 1;
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> io::Result<()>{
+        if self.format == MetricsFormat::Json{
+            writeln!(self.out, "\n]")?;
+        }
+        self.out.flush()
+    }
+}
@@ -3,76 +3,200 @@ pub mod chunk;
 pub mod chunk_context;
 pub mod neighbours;
 pub mod threads;
+pub mod rigid_body;
+pub mod structure;
+pub mod pressure;
+pub mod metrics;
+pub mod dirty;
+pub mod edges;
+pub mod gravity;
+pub mod wind;
 
-use std::{collections::{HashMap, HashSet}, rc::Rc, borrow::{BorrowMut, Borrow}, ops::{DerefMut, Deref}, cell::RefCell, sync::{Arc, Mutex, mpsc::{Sender, Receiver, self}, RwLock}, thread::JoinHandle};
+use std::{collections::{HashMap, HashSet}, time::{Duration, Instant}, rc::Rc, borrow::{BorrowMut, Borrow}, ops::{DerefMut, Deref}, cell::RefCell, sync::{Arc, RwLock}};
+
+use rayon::prelude::*;
 
 use crate::elements::Element;
 
-use self::{chunk::{Chunk, CHUNK_SIZE, CordInChunk}, chunk_context::{ChunkContext, UnsolvedActions::{MissingChunkInsertion, self}}, neighbours::Neighbours, rect::Rect, threads::{TaskMessage, ResultMessage, spawn_field_worker_thread}};
+use self::{chunk::{Chunk, CordInChunk, UpdateStamp, NEVER_UPDATED, chunk_size}, chunk_context::{ChunkNeighbourhood, NeighbourChunk, neighbourhood_index, wake_up, UnsolvedActions::{MissingChunkInsertion, self}}, edges::{WorldEdges, EdgeMode}, gravity::{Gravity, GravityMap, GRAVITY_TILE}, wind::{WindMap, WIND_TILE}, neighbours::Neighbours, rect::Rect, threads::{ChunkTask, ChunkResult, WorkerPool, build_worker_pool, run_chunk_task}, rigid_body::RigidBody, structure::STRUCTURE_CHECK_PERIOD, pressure::PRESSURE_PERIOD, metrics::TickMetrics};
 
 
-const CHUNK_ISIZE: (isize, isize) = (CHUNK_SIZE.0 as isize, CHUNK_SIZE.1 as isize);
+fn chunk_isize() -> (isize, isize){
+    let size = chunk_size();
+    (size.0 as isize, size.1 as isize)
+}
 
 
 type ChunkRef = Arc<RwLock<Chunk>>;
 
 pub struct Field{
     chunks: HashMap<(isize, isize), ChunkRef>,
-    thread_handles: Vec<JoinHandle<()>>,
-    task_sender: Sender<TaskMessage>,
-    result_receiver: Receiver<ResultMessage>,
+    workers: WorkerPool,
     chunks_update_order: Vec<HashSet<(isize, isize)>>,
     chunk_boundaries: Rect<isize>,
+    edges: WorldEdges,
+    gravity: Arc<GravityMap>,
+    wind: Arc<WindMap>,
+    voided_cells: usize,
     updated_cells: Vec<(isize, isize)>,
-    parity: bool
+    updated_this_tick: Vec<(isize, isize)>,
+    metrics: TickMetrics,
+    rigid_bodies: Vec<RigidBody>,
+    structural_mode: bool,
+    ticks: usize,
+    // copy of the global chunk size for cell lookups
+    chunk_size: (isize, isize),
 }
 
 pub type ChunkCord = (isize, isize);
 
-pub fn global_cord_to_chunk_local(position: (isize, isize)) -> (ChunkCord, CordInChunk){
-    let chunk_cord = (position.0 / CHUNK_ISIZE.0, position.1 / CHUNK_ISIZE.1);
-    let cord_in_chunk = (position.0.rem_euclid(CHUNK_ISIZE.0) as usize, position.1.rem_euclid(CHUNK_ISIZE.1) as usize);
+pub fn global_cord_to_chunk_local(position: (isize, isize), chunk_isize: (isize, isize)) -> (ChunkCord, CordInChunk){
+    let chunk_cord = (position.0.div_euclid(chunk_isize.0), position.1.div_euclid(chunk_isize.1));
+    let cord_in_chunk = (position.0.rem_euclid(chunk_isize.0) as usize, position.1.rem_euclid(chunk_isize.1) as usize);
     (chunk_cord, cord_in_chunk)
 }
 
-pub fn local_cord_to_global(cord_in_chunk: CordInChunk, chunk_cord: ChunkCord) -> (isize, isize){
-    (chunk_cord.0 * CHUNK_ISIZE.0 + cord_in_chunk.0 as isize, chunk_cord.1 * CHUNK_ISIZE.1 + cord_in_chunk.1 as isize)
+pub fn local_cord_to_global(cord_in_chunk: CordInChunk, chunk_cord: ChunkCord, chunk_isize: (isize, isize)) -> (isize, isize){
+    (chunk_cord.0 * chunk_isize.0 + cord_in_chunk.0 as isize, chunk_cord.1 * chunk_isize.1 + cord_in_chunk.1 as isize)
 }
 
 impl Field {
 
-    pub fn new(max_field_chunk_count: (usize, usize), number_of_threads: usize) -> Field{
+    pub fn new(max_field_chunk_count: (usize, usize), number_of_threads: usize) -> Result<Field, String>{
+        let chunk_size = chunk_isize();
+        let world_size = (max_field_chunk_count.0 as isize * chunk_size.0, max_field_chunk_count.1 as isize * chunk_size.1);
+        Ok(Field { chunks: HashMap::new(), 
+            chunk_boundaries: Rect::from((0, 0), (max_field_chunk_count.0 as isize, max_field_chunk_count.1 as isize)),
+            edges: WorldEdges::default(),
+            gravity: Arc::new(GravityMap::new(world_size)),
+            wind: Arc::new(WindMap::new(world_size)),
+            voided_cells: 0,
+            workers: build_worker_pool(number_of_threads)?,
+            chunks_update_order: vec![HashSet::new();4],
+            updated_cells: Vec::new(),
+            updated_this_tick: Vec::new(),
+            metrics: TickMetrics::default(),
+            rigid_bodies: Vec::new(),
+            structural_mode: false,
+            ticks: 0,
+            chunk_size, })
+    }
 
-        let (task_sender, task_receiver) = mpsc::channel();
-        let (result_sender, result_receiver) = mpsc::channel();
+    pub fn set_edges(&mut self, edges: WorldEdges) -> Result<(), String>{
+        edges.check((self.chunk_boundaries.right() as usize, self.chunk_boundaries.bottom() as usize))?;
+        self.edges = edges;
+        Ok(())
+    }
 
-        let task_receiver = Arc::new(Mutex::new(task_receiver));
+    pub fn edges(&self) -> WorldEdges{
+        self.edges
+    }
 
-        let handles:Vec<JoinHandle<()>> = (0..number_of_threads).map(|i| {
-            spawn_field_worker_thread(task_receiver.clone(), result_sender.clone(), i)
-        }).collect();
+    pub fn gravity(&self) -> Gravity{
+        self.gravity.world()
+    }
 
-        Field { chunks: HashMap::new(), 
-            chunk_boundaries: Rect::from((0, 0), (max_field_chunk_count.0 as isize, max_field_chunk_count.1 as isize)),
-            parity: false,
-            thread_handles: handles,
-            task_sender,
-            result_receiver,
-            chunks_update_order: vec![HashSet::new();4],
-            updated_cells: Vec::new(), }
+    pub fn gravity_at(&self, position: (isize, isize)) -> Gravity{
+        self.gravity.at(position)
+    }
+
+    pub fn gravity_zones(&self) -> Vec<(Rect<isize>, Gravity)>{
+        self.gravity.zones()
+    }
+
+    pub fn set_gravity(&mut self, gravity: Gravity){
+        Arc::make_mut(&mut self.gravity).set_world(gravity);
+        self.wake_area(self.world_rect());
+    }
+
+    // none goes back to the world gravity
+    pub fn paint_gravity(&mut self, position: (isize, isize), size: (usize, usize), gravity: Option<Gravity>){
+        let area = Rect::from_center(position, size);
+        let gravity_map = Arc::make_mut(&mut self.gravity);
+        for cord in area.into_iter(){
+            gravity_map.paint(cord, gravity);
+        }
+        let tile = GRAVITY_TILE;
+        let tiles = Rect::from((area.left().div_euclid(tile) * tile, area.top().div_euclid(tile) * tile),
+            ((area.right() + tile - 1).div_euclid(tile) * tile, (area.bottom() + tile - 1).div_euclid(tile) * tile));
+        self.wake_area(tiles);
+    }
+
+    pub fn wind_at(&self, position: (isize, isize)) -> (f32, f32){
+        self.wind.at(position)
+    }
+
+    pub fn wind_tiles(&self) -> Vec<(Rect<isize>, (f32, f32))>{
+        self.wind.tiles()
+    }
+
+    pub fn blow(&mut self, position: (isize, isize), size: (usize, usize), velocity: (f32, f32)){
+        let area = Rect::from_center(position, size);
+        let wind = Arc::make_mut(&mut self.wind);
+        let tile = WIND_TILE;
+        let tiles = Rect::from((area.left().div_euclid(tile), area.top().div_euclid(tile)),
+            ((area.right() - 1).div_euclid(tile) + 1, (area.bottom() - 1).div_euclid(tile) + 1));
+        for cord in tiles.into_iter(){
+            wind.blow((cord.0 * tile, cord.1 * tile), velocity);
+        }
+        self.wake_area(Rect::from((tiles.left() * tile, tiles.top() * tile), (tiles.right() * tile, tiles.bottom() * tile)));
+    }
+
+    fn wake_area(&mut self, area: Rect<isize>){
+        for position in area.into_iter(){
+            let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+            if let Some(chunk) = self.chunks.get(&chunk_c){
+                wake_up(&mut chunk.write().unwrap(), c_in_chunk);
+            }
+        }
+    }
+
+    // cells destroyed by void edges since the field was created
+    pub fn voided_cells(&self) -> usize{
+        self.voided_cells
+    }
+
+    fn world_rect(&self) -> Rect<isize>{
+        let chunk_isize = self.chunk_size;
+        Rect::from((self.chunk_boundaries.left() * chunk_isize.0, self.chunk_boundaries.top() * chunk_isize.1),
+            (self.chunk_boundaries.right() * chunk_isize.0, self.chunk_boundaries.bottom() * chunk_isize.1))
+    }
+
+    fn neighbour_chunk(&self, cord: ChunkCord) -> NeighbourChunk{
+        match self.edges.resolve(self.chunk_boundaries, cord) {
+            Ok(cord) => match self.chunks.get(&cord) {
+                Some(chunk) => NeighbourChunk::Chunk(chunk.clone()),
+                None => NeighbourChunk::Missing,
+            },
+            Err(EdgeMode::Void) => NeighbourChunk::Void,
+            Err(_) => NeighbourChunk::Wall,
+        }
     }
 
     pub fn get(&self, position: (isize, isize)) -> Option<Element>{
-        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position);
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
         return self.chunks.get(&chunk_c)?.read().unwrap().get(c_in_chunk);
     }
 
+    pub fn chunk_count(&self) -> usize{
+        self.chunks.len()
+    }
+
+    pub fn active_chunk_count(&self) -> usize{
+        self.chunks.values().filter(|c| c.read().unwrap().needs_updates()).count()
+    }
+
+    pub fn number_of_elements(&self) -> usize{
+        self.chunks.values().map(|c| c.read().unwrap().number_of_elements()).sum()
+    }
+
     pub fn get_chunks(&self) -> Vec<Rect<isize>> {
         let mut rects = Vec::new();
+        let chunk_isize = self.chunk_size;
 
         for (cord, _) in self.chunks.iter(){
-            let top_left = (cord.0 * CHUNK_ISIZE.0, cord.1 * CHUNK_ISIZE.1);
-            let bottom_right = ((cord.0 + 1) * CHUNK_ISIZE.0, (cord.1 + 1) * CHUNK_ISIZE.1);
+            let top_left = (cord.0 * chunk_isize.0, cord.1 * chunk_isize.1);
+            let bottom_right = ((cord.0 + 1) * chunk_isize.0, (cord.1 + 1) * chunk_isize.1);
             rects.push(Rect::from(top_left, bottom_right));
         }
 
@@ -81,23 +205,60 @@ impl Field {
 
     pub fn get_chunks_update_rects(&self) -> Vec<Rect<isize>>{
         let mut rects = Vec::new();
+        let chunk_isize = self.chunk_size;
 
         for (cord, chunk) in self.chunks.iter(){
             let rect = chunk.read().unwrap().get_update_rect();
-            let top_left = (cord.0 * CHUNK_ISIZE.0 + rect.left() as isize, 
-            cord.1 * CHUNK_ISIZE.1 + rect.top() as isize);
-            let bottom_right = (cord.0  * CHUNK_ISIZE.0 + rect.right() as isize, 
-            cord.1 * CHUNK_ISIZE.1 + rect.bottom() as isize);
+            let top_left = (cord.0 * chunk_isize.0 + rect.left() as isize, 
+            cord.1 * chunk_isize.1 + rect.top() as isize);
+            let bottom_right = (cord.0  * chunk_isize.0 + rect.right() as isize, 
+            cord.1 * chunk_isize.1 + rect.bottom() as isize);
             rects.push(Rect::from(top_left, bottom_right));
         }
 
         rects
     }
 
+    // ticks only advance once the update is finished
+    fn stamp(&self) -> UpdateStamp{
+        self.ticks as UpdateStamp + 1
+    }
+
+    pub fn last_stamp(&self) -> UpdateStamp{
+        self.ticks as UpdateStamp
+    }
+
+    pub fn stamp_at(&self, position: (isize, isize)) -> Option<UpdateStamp>{
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+        Some(self.chunks.get(&chunk_c)?.read().unwrap().stamp(c_in_chunk))
+    }
+
+    pub fn kept_alive_at(&self, position: (isize, isize)) -> bool{
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+        self.chunks.get(&chunk_c).is_some_and(|c| c.read().unwrap().kept_alive(c_in_chunk))
+    }
+
+    pub fn updated_this_tick(&self) -> &[(isize, isize)]{
+        &self.updated_this_tick
+    }
+
+    pub fn last_tick_metrics(&self) -> &TickMetrics{
+        &self.metrics
+    }
+
+    pub fn chunk_update_costs(&self) -> Vec<(Rect<isize>, Duration)>{
+        let chunk_isize = self.chunk_size;
+        self.metrics.chunk_times.iter().map(|(cord, cost)| {
+            let top_left = (cord.0 * chunk_isize.0, cord.1 * chunk_isize.1);
+            let bottom_right = ((cord.0 + 1) * chunk_isize.0, (cord.1 + 1) * chunk_isize.1);
+            (Rect::from(top_left, bottom_right), *cost)
+        }).collect()
+    }
+
     pub fn load_pixels(&mut self) -> Vec<((isize, isize), [u8;4])>{
         let mut result = Vec::new();
         for updated_pix in self.updated_cells.iter(){
-            let (chunk_cord, cord_in_chunk) = global_cord_to_chunk_local(*updated_pix);
+            let (chunk_cord, cord_in_chunk) = global_cord_to_chunk_local(*updated_pix, self.chunk_size);
             let chunk = self.chunks.get(&chunk_cord);
             let color = if let Some(chunk) = chunk{
                 match chunk.read().unwrap().get(cord_in_chunk) {
@@ -115,6 +276,7 @@ impl Field {
     }
 
     pub fn set_in_area(&mut self, position: (isize, isize), size: (usize, usize), element: Option<Element>){
+        let chunk_isize = self.chunk_size;
         let mut top = position.1 - size.1 as isize/2;
         if top < 0{
             top = 0;
@@ -124,12 +286,12 @@ impl Field {
             left = 0;
         }
         let mut bottom = position.1+size.1 as isize/2+size.1 as isize%2;
-        if bottom >= self.chunk_boundaries.bottom() * CHUNK_ISIZE.1 {
-            bottom = self.chunk_boundaries.bottom() * CHUNK_ISIZE.1;
+        if bottom >= self.chunk_boundaries.bottom() * chunk_isize.1 {
+            bottom = self.chunk_boundaries.bottom() * chunk_isize.1;
         }
         let mut right = position.0+size.0 as isize/2+size.0 as isize%2;
-        if right >= self.chunk_boundaries.right() * CHUNK_ISIZE.0 {
-            right = self.chunk_boundaries.right() * CHUNK_ISIZE.0;
+        if right >= self.chunk_boundaries.right() * chunk_isize.0 {
+            right = self.chunk_boundaries.right() * chunk_isize.0;
         }
         for y in top..bottom{
             for x in left..right{
@@ -139,13 +301,13 @@ impl Field {
     }
 
     pub fn set(&mut self, position: (isize, isize), element: Option<Element>){
-        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position);
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
         if !self.chunks.contains_key(&chunk_c){
             self.insert_chunk(chunk_c);
         }
         let chunk = self.chunks.get(&chunk_c).unwrap();
         match element {
-            Some(e) => chunk.write().unwrap().set(c_in_chunk, e, self.parity),
+            Some(e) => chunk.write().unwrap().set(c_in_chunk, e, NEVER_UPDATED),
             None => chunk.write().unwrap().clear(c_in_chunk),
         }
         chunk.write().unwrap().add_point_in_update_cycle_with_neighbourhood(c_in_chunk);
@@ -169,84 +331,216 @@ impl Field {
     }
 
     fn insert_chunk(&mut self, cord: ChunkCord){
-        self.chunks.insert(cord,  Arc::new(RwLock::new(Chunk::new(self.parity))));
-        // let order = Field::get_chunk_order(cord);
-        // self.chunks_update_order[order].insert(cord);
+        self.chunks.insert(cord,  Arc::new(RwLock::new(Chunk::new())));
+        let order = Field::get_chunk_order(cord);
+        self.chunks_update_order[order].insert(cord);
     }
 
     fn delete_chunk(&mut self, cord: ChunkCord){
         self.chunks.remove(&cord);
-        // let order = Field::get_chunk_order(cord);
-        // self.chunks_update_order[order].remove(&cord);
+        let order = Field::get_chunk_order(cord);
+        self.chunks_update_order[order].remove(&cord);
+    }
+
+    // counts as updated this tick and wakes up the neighbours
+    fn set_deferred(&mut self, position: (isize, isize), element: Option<Element>){
+        let position = match self.edges.resolve(self.world_rect(), position) {
+            Ok(position) => position,
+            Err(EdgeMode::Void) => {
+                if element.is_some(){
+                    self.voided_cells += 1;
+                }
+                return;
+            },
+            Err(_) => return,
+        };
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+        if element.is_none() && !self.chunks.contains_key(&chunk_c){
+            return;
+        }
+        if !self.chunks.contains_key(&chunk_c){
+            self.insert_chunk(chunk_c);
+        }
+        {
+            let mut chunk = self.chunks.get(&chunk_c).unwrap().write().unwrap();
+            match element {
+                Some(e) => chunk.set(c_in_chunk, e, self.stamp()),
+                None => chunk.clear(c_in_chunk),
+            }
+            chunk.add_point_in_update_cycle(c_in_chunk);
+        }
+        for neighbour in Neighbours::of(position){
+            self.wake_up_deferred(neighbour);
+        }
+        self.updated_this_tick.push(position);
+        self.updated_cells.push(position);
+    }
+
+    // an earlier action may have filled the cell, then the nearest free one is used
+    fn insert_deferred(&mut self, position: (isize, isize), element: Element){
+        let target = match self.edges.resolve(self.world_rect(), position) {
+            Ok(target) if self.get(target).is_some() => {
+                let size = self.world_rect();
+                match self.free_cell_near(target, &HashSet::new(), size.right().max(size.bottom())) {
+                    Some(free) => free,
+                    None => return,
+                }
+            },
+            _ => position,
+        };
+        self.set_deferred(target, Some(element));
+    }
+
+    fn keep_alive_deferred(&mut self, position: (isize, isize)){
+        let Ok(position) = self.edges.resolve(self.world_rect(), position) else {return};
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+        if let Some(chunk) = self.chunks.get(&chunk_c){
+            chunk.write().unwrap().add_point_in_update_cycle(c_in_chunk);
+        }
+    }
+
+    fn wake_up_deferred(&mut self, position: (isize, isize)){
+        let Ok(position) = self.edges.resolve(self.world_rect(), position) else {return};
+        let (chunk_c, c_in_chunk) = global_cord_to_chunk_local(position, self.chunk_size);
+        if let Some(chunk) = self.chunks.get(&chunk_c){
+            wake_up(&mut chunk.write().unwrap(), c_in_chunk);
+        }
     }
 
     fn solve_unsolved_action(&mut self, unsolved_actions: Vec<UnsolvedActions>){
         for unsolved_action in unsolved_actions{
             match unsolved_action {
                 MissingChunkInsertion { chunk_cord, insertion_cord, elementToInsert } =>{
-                    if !self.chunks.contains_key(&chunk_cord){
-                        self.insert_chunk(chunk_cord);
+                    self.insert_deferred(local_cord_to_global(insertion_cord, chunk_cord, self.chunk_size), elementToInsert);
+                },
+                UnsolvedActions::Set { position, element } => self.insert_deferred(position, element),
+                UnsolvedActions::Move { from, to, element, original } => {
+                    let target = self.edges.resolve(self.world_rect(), to);
+                    let source_unchanged = self.get(from).is_some_and(|e| e.same_as(&original));
+                    if source_unchanged && (target == Err(EdgeMode::Void) || target.is_ok_and(|to| self.get(to).is_none())){
+                        self.set_deferred(to, Some(element));
+                        self.set_deferred(from, None);
+                    }
+                    else{
+                        self.keep_alive_deferred(from);
                     }
-                    self.chunks.get(&chunk_cord).unwrap().write().unwrap().set(insertion_cord, elementToInsert, !self.parity);
                 },
+                UnsolvedActions::Clear { position } => self.set_deferred(position, None),
+                UnsolvedActions::KeepAlive { position } => self.keep_alive_deferred(position),
+                UnsolvedActions::WakeUp { position } => self.wake_up_deferred(position),
             }
         }
     }
 
     pub fn update(&mut self){
+        let tick_start = Instant::now();
+        let mut metrics = TickMetrics { tick: self.ticks, ..TickMetrics::default() };
+        let voided_before = self.voided_cells;
 
         for (_, c) in self.chunks.iter(){
             c.write().unwrap().update_rect();
         }
 
         let mut unsolved_actions = Vec::new();
+        self.updated_this_tick.clear();
 
-        // for update_order in 0..4 {
-            let mut spawned = 0;
-            for (chunk_cord, chunk) in self.chunks.iter(){
+        // chunks of one order are never adjacent
+        for update_order in 0..4 {
+            let stage_start = Instant::now();
+            let mut tasks = Vec::new();
+            for chunk_cord in self.chunks_update_order[update_order].iter(){
+                let chunk = self.chunks.get(chunk_cord).unwrap();
 
-                if !self.chunks.get(chunk_cord).unwrap().read().unwrap().needs_updates(){
+                if !chunk.read().unwrap().needs_updates(){
                     continue;
                 }
 
-                let mut neighbours = HashMap::new();
-                for neighbour in Neighbours::of(*chunk_cord).with_boundaries(self.chunk_boundaries){
-                    let chunk_opt = self.chunks.get(&neighbour);
-                    if let Some(chunk) = chunk_opt{
-                        neighbours.insert(neighbour, Some(chunk.clone()));
-                    }
-                    else{
-                        neighbours.insert(neighbour, None);
-                    }
+                let mut neighbours: ChunkNeighbourhood = Default::default();
+                for neighbour in Neighbours::of(*chunk_cord){
+                    let index = neighbourhood_index(*chunk_cord, neighbour).unwrap();
+                    neighbours[index] = self.neighbour_chunk(neighbour);
                 }
 
-                let chunk = chunk.clone();
-
-                self.task_sender.send(TaskMessage::UpdateChunk(ChunkContext::new(
-                    chunk, 
-                    *chunk_cord, 
+                tasks.push(ChunkTask{
+                    chunk: chunk.clone(), 
+                    chunk_cord: *chunk_cord, 
                     neighbours, 
-                    self.parity))).ok();
-                
-                spawned += 1;
+                    gravity: self.gravity.clone(),
+                    wind: self.wind.clone(),
+                    stamp: self.stamp()});
             }
-
-            for _ in 0..spawned{
-                match self.result_receiver.recv().unwrap() {
-                    ResultMessage::UnsolvedActions { unsolved, updated } => {
-                        unsolved_actions.extend(unsolved);
-                        self.updated_cells.extend(updated);
-                    },
-                }
+            metrics.dispatch += stage_start.elapsed();
+
+            let stage_start = Instant::now();
+            let results: Vec<ChunkResult> = self.workers.install(|| tasks.into_par_iter().map(run_chunk_task).collect());
+            for ChunkResult { unsolved, updated, chunk, cost, processed, voided } in results{
+                unsolved_actions.extend(unsolved);
+                self.voided_cells += voided;
+                metrics.cells_changed += updated.len();
+                metrics.cells_processed += processed;
+                metrics.chunk_times.push((chunk, cost));
+                let (world, edges) = (self.world_rect(), self.edges);
+                let updated = updated.into_iter().filter_map(|position| edges.resolve(world, position).ok());
+                self.updated_this_tick.extend(updated.clone());
+                self.updated_cells.extend(updated);
             }
+            metrics.workers += stage_start.elapsed();
+        }
 
-        // }
-        self.parity = !self.parity;
-
+        let stage_start = Instant::now();
         self.solve_unsolved_action(unsolved_actions);
+        metrics.unsolved_actions = stage_start.elapsed();
+
+        if !self.wind.is_calm(){
+            Arc::make_mut(&mut self.wind).decay();
+        }
+
+        let stage_start = Instant::now();
+        self.update_rigid_bodies();
+        metrics.rigid_bodies = stage_start.elapsed();
 
+        let stage_start = Instant::now();
+        if self.structural_mode && self.ticks.is_multiple_of(STRUCTURE_CHECK_PERIOD){
+            self.collapse_unsupported();
+        }
+        metrics.structure = stage_start.elapsed();
+
+        let stage_start = Instant::now();
+        if self.ticks.is_multiple_of(PRESSURE_PERIOD){
+            self.equalise_pressure();
+        }
+        metrics.pressure = stage_start.elapsed();
+        self.ticks += 1;
+
+        let stage_start = Instant::now();
         self.remove_empty_chunks();
+        metrics.remove_empty_chunks = stage_start.elapsed();
+
+        metrics.cells_voided = self.voided_cells - voided_before;
+        metrics.chunks = self.chunks.len();
+        metrics.total = tick_start.elapsed();
+        self.metrics = metrics;
+    }
+}
 
-        
+impl Drop for Field {
+    fn drop(&mut self) {
+        self.workers.shutdown();
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn deferred_insertions_into_the_same_cell_keep_both_elements(){
+        let mut field = Field::new((2, 2), 1).unwrap();
+        let insertion = |element| MissingChunkInsertion { chunk_cord: (1, 1), insertion_cord: (3, 3), elementToInsert: element };
+        field.solve_unsolved_action(vec![insertion(Element::sand()), insertion(Element::water())]);
+
+        let target = local_cord_to_global((3, 3), (1, 1), field.chunk_size);
+        assert_eq!(field.number_of_elements(), 2);
+        assert!(field.get(target).is_some_and(|e| e.same_as(&Element::sand())));
+    }
+}
@@ -1,52 +1,42 @@
 use super::rect::Rect;
 
 
+const ALL: [(isize, isize); 8] = [(-1, -1), (0, -1), (1, -1), (-1, 0), (1, 0), (-1, 1), (0, 1), (1, 1)];
+const DIRECT: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+const HORISONTAL: [(isize, isize); 2] = [(-1, 0), (1, 0)];
+const VERTICAL: [(isize, isize); 2] = [(0, -1), (0, 1)];
+
 #[derive(Clone)]
 pub struct Neighbours{
     current: usize,
-    all_neighbours: Vec<(isize, isize)>,
+    len: usize,
+    all_neighbours: [(isize, isize); 8],
     boundaries: Option<Rect<isize>>
 }
 
 impl Neighbours {
+    fn with_offsets(position: (isize, isize), offsets: &[(isize, isize)]) -> Neighbours{
+        let mut all_neighbours = [(0, 0); 8];
+        for (neighbour, offset) in all_neighbours.iter_mut().zip(offsets){
+            *neighbour = (position.0 + offset.0, position.1 + offset.1);
+        }
+        Neighbours { current: 0, len: offsets.len(), all_neighbours, boundaries: None }
+    }
+
     pub fn of(position: (isize, isize)) -> Neighbours{
-        Neighbours { current: 0, all_neighbours: vec![
-            (position.0 - 1, position.1 - 1),
-            (position.0    , position.1 - 1),
-            (position.0 + 1, position.1 - 1),
-            (position.0 - 1, position.1    ),
-            (position.0 + 1, position.1    ),
-            (position.0 - 1, position.1 + 1),
-            (position.0    , position.1 + 1),
-            (position.0 + 1, position.1 + 1),
-        ],
-            boundaries: None, }
+        Neighbours::with_offsets(position, &ALL)
     }
 
     pub fn direct_of(position: (isize, isize)) -> Neighbours{
-        Neighbours { current: 0, all_neighbours: vec![
-            (position.0    , position.1 - 1),
-            (position.0 - 1, position.1    ),
-            (position.0 + 1, position.1    ),
-            (position.0    , position.1 + 1),
-        ],
-        boundaries: None, }
+        Neighbours::with_offsets(position, &DIRECT)
     }
 
     pub fn horisontal_of(position: (isize, isize)) -> Neighbours{
-        Neighbours { current: 0, all_neighbours: vec![
-            (position.0 - 1, position.1    ),
-            (position.0 + 1, position.1    ),
-        ],
-        boundaries: None, }
+        Neighbours::with_offsets(position, &HORISONTAL)
     }
     
     pub fn vertical_of(position: (isize, isize)) -> Neighbours{
-        Neighbours { current: 0, all_neighbours: vec![
-            (position.0    , position.1 - 1),
-            (position.0    , position.1 + 1),
-        ],
-        boundaries: None, }
+        Neighbours::with_offsets(position, &VERTICAL)
     }
 
     pub fn with_boundaries(mut self, boundaries: Rect<isize>) -> Neighbours{
@@ -59,16 +49,13 @@ impl Iterator for Neighbours {
     type Item = (isize, isize);
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.all_neighbours.len(){
-            return None;
-        }
-        let result = self.all_neighbours[self.current];
-        self.current += 1;
-        if let Some(boundary) = self.boundaries{
-            if !boundary.is_inside(result){
-                return self.next();
+        while self.current < self.len{
+            let result = self.all_neighbours[self.current];
+            self.current += 1;
+            if self.boundaries.is_none_or(|boundary| boundary.is_inside(result)){
+                return Some(result);
             }
         }
-        Some(result)
+        None
     }
 }
\ No newline at end of file
@@ -0,0 +1,129 @@
+use std::collections::{HashSet, VecDeque};
+
+use crate::elements::{Element, material::Material};
+
+use super::{Field, chunk::chunk_size, local_cord_to_global, gravity::step, neighbours::Neighbours, edges::EdgeMode};
+
+pub const PRESSURE_PERIOD: usize = 5;
+// large bodies level out about as fast as small ones
+const CELLS_PER_MOVE: usize = 32;
+// a fully cohesive liquid keeps its surface up to this many cells above the rest
+const MAX_TENSION: f64 = 4.;
+
+struct Body{
+    material: Material,
+    cells: Vec<(isize, isize)>,
+}
+
+impl Field {
+    fn depth(&self, position: (isize, isize)) -> isize{
+        let down = self.gravity_at(position).down();
+        position.0 * down.0 + position.1 * down.1
+    }
+
+    fn is_pressurised(&self, element: &Element, position: (isize, isize)) -> bool{
+        element.liquid().is_some() && !self.gravity_at(position).is_zero()
+    }
+
+    fn neighbours_in_world(&self, position: (isize, isize)) -> impl Iterator<Item = (isize, isize)> + '_{
+        Neighbours::direct_of(position).filter_map(|cord| self.edges.resolve(self.world_rect(), cord).ok())
+    }
+
+    fn is_free_surface(&self, position: (isize, isize)) -> bool{
+        let above = step(position, self.gravity_at(position).down(), -1);
+        match self.edges.resolve(self.world_rect(), above) {
+            Ok(above) => self.get(above).is_none(),
+            Err(mode) => mode == EdgeMode::Void,
+        }
+    }
+
+    fn is_supported(&self, position: (isize, isize)) -> bool{
+        let below = step(position, self.gravity_at(position).down(), 1);
+        match self.edges.resolve(self.world_rect(), below) {
+            Ok(below) => self.get(below).is_some(),
+            Err(mode) => mode == EdgeMode::Wall,
+        }
+    }
+
+    // seeded from chunks that are still moving
+    fn liquid_bodies(&self) -> Vec<Body>{
+        let size = chunk_size();
+        let mut visited = HashSet::new();
+        let mut bodies = Vec::new();
+        for (chunk_cord, chunk) in self.chunks.iter(){
+            let chunk = chunk.read().unwrap();
+            if !chunk.needs_updates(){
+                continue;
+            }
+            for y in 0..size.1{
+                for x in 0..size.0{
+                    let position = local_cord_to_global((x, y), *chunk_cord, self.chunk_size);
+                    let material = match chunk.get((x, y)) {
+                        Some(e) if self.is_pressurised(&e, position) => e.get_type().material(),
+                        _ => continue,
+                    };
+                    if visited.contains(&position){
+                        continue;
+                    }
+                    bodies.push(self.flood_body(position, material, &mut visited));
+                }
+            }
+        }
+        bodies
+    }
+
+    fn flood_body(&self, start: (isize, isize), material: Material, visited: &mut HashSet<(isize, isize)>) -> Body{
+        let mut cells = Vec::new();
+        let mut queue = VecDeque::from([start]);
+        visited.insert(start);
+        while let Some(cord) = queue.pop_front(){
+            cells.push(cord);
+            for next in self.neighbours_in_world(cord){
+                if visited.contains(&next){
+                    continue;
+                }
+                if let Some(e) = self.get(next){
+                    if e.get_type().material() == material && self.is_pressurised(&e, next){
+                        visited.insert(next);
+                        queue.push_back(next);
+                    }
+                }
+            }
+        }
+        Body { material, cells }
+    }
+
+    // highest free surface cells move to the lowest empty cells next to the body
+    pub(super) fn equalise_pressure(&mut self){
+        for body in self.liquid_bodies(){
+            let cells: HashSet<(isize, isize)> = body.cells.iter().copied().collect();
+
+            let mut sources: Vec<(isize, isize)> = body.cells.iter().copied()
+                .filter(|cord| self.is_free_surface(*cord))
+                .collect();
+            let mut targets: Vec<(isize, isize)> = body.cells.iter()
+                .flat_map(|cord| self.neighbours_in_world(*cord))
+                .filter(|cord| !cells.contains(cord))
+                .filter(|cord| self.get(*cord).is_none() && self.is_supported(*cord))
+                .collect::<HashSet<_>>().into_iter().collect();
+
+            sources.sort_by_key(|cord| self.depth(*cord));
+            targets.sort_by_key(|cord| -self.depth(*cord));
+
+            let moves = 1 + body.cells.len() / CELLS_PER_MOVE;
+            let tension = 1 + (body.material.properties().cohesion * MAX_TENSION) as isize;
+            for (source, target) in sources.into_iter().zip(targets).take(moves){
+                // a step of one would just swap the cell back and forth
+                if self.depth(target) <= self.depth(source) + tension{
+                    break;
+                }
+                let element = match self.get(source) {
+                    Some(e) if e.get_type().material() == body.material => e,
+                    _ => continue,
+                };
+                self.set(source, None);
+                self.set(target, Some(element.refresh()));
+            }
+        }
+    }
+}
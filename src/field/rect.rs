@@ -62,6 +62,13 @@ impl Rect<isize> {
     }
 }
 
+impl<T> Default for Rect<T>
+where T: PartialEq+Copy+One+Zero+Add+Ord+Clone{
+    fn default() -> Self {
+        Rect::new()
+    }
+}
+
 impl<T> Rect<T>
 where T: PartialEq+Copy+One+Zero+Add+Ord+Clone{
 
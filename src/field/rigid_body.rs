@@ -0,0 +1,302 @@
+use std::{collections::HashSet, mem};
+
+use crate::elements::{Element, ElementType};
+
+use super::Field;
+
+const GRAVITY: f64 = 0.15;
+const MAX_SPEED: f64 = 6.;
+const FRICTION: f64 = 0.5;
+const TIP_ACCELERATION: f64 = 0.01;
+const ANGULAR_DAMPING: f64 = 0.9;
+const DISPLACE_SEARCH_DISTANCE: isize = 16;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum RigidBodyKind{
+    Crate,
+    Boulder,
+}
+
+impl RigidBodyKind {
+    pub fn material(&self) -> Element{
+        match self {
+            RigidBodyKind::Crate => Element::wood(),
+            RigidBodyKind::Boulder => Element::stone(),
+        }
+    }
+
+    fn is_material(&self, element: Element) -> bool{
+        matches!((self, element.get_type()), (RigidBodyKind::Crate, ElementType::Wood) | (RigidBodyKind::Boulder, ElementType::Stone))
+    }
+
+    fn fills(&self, cord: (usize, usize), size: (usize, usize)) -> bool{
+        match self {
+            RigidBodyKind::Crate => true,
+            RigidBodyKind::Boulder => {
+                let dx = cord.0 as f64 + 0.5 - size.0 as f64 / 2.;
+                let dy = cord.1 as f64 + 0.5 - size.1 as f64 / 2.;
+                (dx / size.0 as f64).powi(2) + (dy / size.1 as f64).powi(2) <= 0.25
+            },
+        }
+    }
+
+    // cells per tick
+    fn break_speed(&self) -> f64{
+        match self {
+            RigidBodyKind::Crate => 2.5,
+            RigidBodyKind::Boulder => 4.,
+        }
+    }
+}
+
+pub struct RigidBody{
+    kind: RigidBodyKind,
+    size: (usize, usize),
+    shape: Vec<Option<Element>>,
+    position: (f64, f64),
+    velocity: (f64, f64),
+    angle: f64,
+    angular_velocity: f64,
+    cells: Vec<((isize, isize), usize)>,
+}
+
+impl RigidBody {
+    pub fn new(kind: RigidBodyKind, center: (isize, isize), size: (usize, usize)) -> RigidBody{
+        let shape = (0..size.0 * size.1)
+            .map(|i| if kind.fills((i % size.0, i / size.0), size) {Some(kind.material())} else {None})
+            .collect();
+        let left = center.0 - size.0 as isize / 2;
+        let top = center.1 - size.1 as isize / 2;
+        RigidBody { kind, size, shape,
+            position: (left as f64 + size.0 as f64 / 2., top as f64 + size.1 as f64 / 2.),
+            velocity: (0., 0.),
+            angle: 0.,
+            angular_velocity: 0.,
+            cells: Vec::new() }
+    }
+
+    fn radius(&self) -> f64{
+        ((self.size.0 * self.size.0 + self.size.1 * self.size.1) as f64).sqrt() / 2. + 1.
+    }
+
+    fn footprint(&self, position: (f64, f64), angle: f64) -> Vec<((isize, isize), usize)>{
+        let (sin, cos) = angle.sin_cos();
+        let radius = self.radius();
+        let mut cells = Vec::new();
+        for y in (position.1 - radius).floor() as isize..=(position.1 + radius).ceil() as isize{
+            for x in (position.0 - radius).floor() as isize..=(position.0 + radius).ceil() as isize{
+                let dx = x as f64 + 0.5 - position.0;
+                let dy = y as f64 + 0.5 - position.1;
+                let local_x = dx * cos + dy * sin + self.size.0 as f64 / 2.;
+                let local_y = -dx * sin + dy * cos + self.size.1 as f64 / 2.;
+                if local_x < 0. || local_y < 0.{
+                    continue;
+                }
+                let (local_x, local_y) = (local_x as usize, local_y as usize);
+                if local_x >= self.size.0 || local_y >= self.size.1{
+                    continue;
+                }
+                let index = local_y * self.size.0 + local_x;
+                if self.shape[index].is_some(){
+                    cells.push(((x, y), index));
+                }
+            }
+        }
+        cells
+    }
+
+    fn collisions(&self, field: &Field, own: &HashSet<(isize, isize)>, position: (f64, f64), angle: f64) -> Vec<(isize, isize)>{
+        self.footprint(position, angle).into_iter()
+            .map(|(cord, _)| cord)
+            .filter(|cord| !own.contains(cord) && field.is_obstacle(*cord))
+            .collect()
+    }
+
+    fn shatter(&self, field: &mut Field){
+        for (cord, _) in self.cells.iter(){
+            field.set(*cord, Some(Element::debris()));
+        }
+    }
+
+    // nothing is moved when a displaced cell has nowhere to go
+    fn move_to(&mut self, field: &mut Field, own: &HashSet<(isize, isize)>, new_cells: Vec<((isize, isize), usize)>) -> bool{
+        let new_set: HashSet<(isize, isize)> = new_cells.iter().map(|(cord, _)| *cord).collect();
+
+        let mut vacated: Vec<(isize, isize)> = self.cells.iter()
+            .map(|(cord, _)| *cord)
+            .filter(|cord| !new_set.contains(cord))
+            .collect();
+        vacated.sort_by_key(|cord| -cord.1);
+
+        let displaced: Vec<((isize, isize), Element)> = new_cells.iter()
+            .filter(|(cord, _)| !own.contains(cord))
+            .filter_map(|(cord, _)| field.get(*cord).map(|element| (*cord, element)))
+            .collect();
+
+        let mut blocked = new_set.clone();
+        let mut placements = Vec::new();
+        for (cord, element) in displaced.iter(){
+            let free = match vacated.pop() {
+                Some(free) => free,
+                None => match field.free_cell_near(*cord, &blocked, DISPLACE_SEARCH_DISTANCE) {
+                    Some(free) => free,
+                    None => return false,
+                },
+            };
+            blocked.insert(free);
+            placements.push((free, *element));
+        }
+
+        for (cord, _) in self.cells.iter(){
+            if !new_set.contains(cord){
+                field.set(*cord, None);
+            }
+        }
+        for (cord, index) in new_cells.iter(){
+            field.set(*cord, self.shape[*index]);
+        }
+        for (free, element) in placements{
+            field.set(free, Some(element));
+        }
+
+        self.cells = new_cells;
+        true
+    }
+
+    pub fn update(&mut self, field: &mut Field) -> bool{
+        let kind = self.kind;
+        let shape = &mut self.shape;
+        self.cells.retain(|(cord, index)| {
+            let intact = field.get(*cord).is_some_and(|e| kind.is_material(e));
+            if !intact{
+                shape[*index] = None;
+            }
+            intact
+        });
+        if self.cells.is_empty(){
+            return false;
+        }
+
+        // normalised so diagonal gravity isn't stronger
+        let down = field.gravity_at((self.position.0.floor() as isize, self.position.1.floor() as isize)).down();
+        let length = f64::max(1., ((down.0 * down.0 + down.1 * down.1) as f64).sqrt());
+        let down = (down.0 as f64 / length, down.1 as f64 / length);
+        let along = |v: (f64, f64)| v.0 * down.0 + v.1 * down.1;
+
+        self.velocity = (self.velocity.0 + down.0 * GRAVITY, self.velocity.1 + down.1 * GRAVITY);
+        let falling = along(self.velocity);
+        if falling > MAX_SPEED{
+            self.velocity = (self.velocity.0 - down.0 * (falling - MAX_SPEED), self.velocity.1 - down.1 * (falling - MAX_SPEED));
+        }
+        let own: HashSet<(isize, isize)> = self.cells.iter().map(|(cord, _)| *cord).collect();
+        let (previous_position, previous_angle) = (self.position, self.angle);
+
+        let steps = f64::max(f64::max(self.velocity.0.abs(), self.velocity.1.abs()),
+            self.angular_velocity.abs() * self.radius()).ceil().max(1.) as usize;
+        let mut contacts = Vec::new();
+        for _ in 0..steps{
+            if contacts.is_empty(){
+                let next = (self.position.0 + self.velocity.0 / steps as f64, self.position.1 + self.velocity.1 / steps as f64);
+                contacts = self.collisions(field, &own, next, self.angle);
+                if contacts.is_empty(){
+                    self.position = next;
+                }
+            }
+
+            // rotating around a contact point lifts the centre of mass a bit
+            let next_angle = self.angle + self.angular_velocity / steps as f64;
+            let pivot = [self.position, (self.position.0 - down.0, self.position.1 - down.1)].into_iter()
+                .find(|p| self.collisions(field, &own, *p, next_angle).is_empty());
+            match pivot {
+                Some(p) => {
+                    self.position = p;
+                    self.angle = next_angle;
+                },
+                None => self.angular_velocity = 0.,
+            }
+        }
+
+        if !contacts.is_empty(){
+            let speed = self.velocity.0.hypot(self.velocity.1);
+            if speed > self.kind.break_speed(){
+                self.shatter(field);
+                return false;
+            }
+            let falling = along(self.velocity);
+            self.velocity = ((self.velocity.0 - down.0 * falling) * FRICTION, (self.velocity.1 - down.1 * falling) * FRICTION);
+
+            // offsets across gravity, positive ones tip the body clockwise
+            let offsets = contacts.iter().map(|cord| (cord.0 as f64 + 0.5 - self.position.0) * down.1 -
+                (cord.1 as f64 + 0.5 - self.position.1) * down.0);
+            let (min, max) = offsets.fold((f64::MAX, f64::MIN), |(min, max), o| (min.min(o), max.max(o)));
+            if min > 0.5{
+                self.angular_velocity -= TIP_ACCELERATION;
+            }
+            else if max < -0.5{
+                self.angular_velocity += TIP_ACCELERATION;
+            }
+            else{
+                self.angular_velocity *= ANGULAR_DAMPING;
+            }
+        }
+
+        let new_cells = self.footprint(self.position, self.angle);
+        if (new_cells.len() != self.cells.len() || new_cells.iter().any(|(cord, _)| !own.contains(cord))) &&
+            !self.move_to(field, &own, new_cells){
+            self.position = previous_position;
+            self.angle = previous_angle;
+            self.velocity = (0., 0.);
+            self.angular_velocity = 0.;
+        }
+        true
+    }
+}
+
+impl Field {
+    fn is_inside(&self, position: (isize, isize)) -> bool{
+        let chunk_size = self.chunk_size;
+        position.0 >= 0 && position.1 >= 0 &&
+        position.0 < self.chunk_boundaries.right() * chunk_size.0 &&
+        position.1 < self.chunk_boundaries.bottom() * chunk_size.1
+    }
+
+    fn is_obstacle(&self, position: (isize, isize)) -> bool{
+        !self.is_inside(position) || self.get(position).is_some_and(|e| e.solid().is_some())
+    }
+
+    // nearest free cell to `cord`, looking up first, then to the sides and down, ring by ring
+    pub(super) fn free_cell_near(&self, cord: (isize, isize), blocked: &HashSet<(isize, isize)>, max_distance: isize) -> Option<(isize, isize)>{
+        for distance in 1..=max_distance{
+            let ring = (-distance..=distance).flat_map(|x| [(x, -distance), (x, distance)])
+                .chain((1 - distance..distance).flat_map(|y| [(-distance, y), (distance, y)]));
+            let mut ring: Vec<(isize, isize)> = ring.map(|(x, y)| (cord.0 + x, cord.1 + y)).collect();
+            ring.sort_by_key(|free| (free.1, (free.0 - cord.0).abs()));
+            let found = ring.into_iter()
+                .find(|free| self.is_inside(*free) && !blocked.contains(free) && self.get(*free).is_none());
+            if found.is_some(){
+                return found;
+            }
+        }
+        None
+    }
+
+    // refuses to spawn over solids or where displaced cells can't make room
+    pub fn spawn_rigid_body(&mut self, center: (isize, isize), size: (usize, usize), kind: RigidBodyKind) -> bool{
+        let mut body = RigidBody::new(kind, center, size);
+        let cells: Vec<((isize, isize), usize)> = body.footprint(body.position, body.angle).into_iter()
+            .filter(|(cord, _)| self.is_inside(*cord))
+            .collect();
+        if cells.iter().any(|(cord, _)| self.is_obstacle(*cord)) || !body.move_to(self, &HashSet::new(), cells){
+            return false;
+        }
+        self.rigid_bodies.push(body);
+        true
+    }
+
+    pub(super) fn update_rigid_bodies(&mut self){
+        let mut bodies = mem::take(&mut self.rigid_bodies);
+        bodies.retain_mut(|body| body.update(self));
+        bodies.extend(mem::take(&mut self.rigid_bodies));
+        self.rigid_bodies = bodies;
+    }
+}
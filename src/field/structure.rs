@@ -0,0 +1,87 @@
+use std::collections::{HashMap, VecDeque};
+
+use crate::elements::Element;
+
+use super::{Field, chunk::chunk_size, local_cord_to_global, gravity::step};
+
+pub const STRUCTURE_CHECK_PERIOD: usize = 10;
+
+impl Field {
+    pub fn structural_mode(&self) -> bool{
+        self.structural_mode
+    }
+
+    pub fn set_structural_mode(&mut self, value: bool){
+        self.structural_mode = value;
+    }
+
+    fn structural_cells(&self) -> HashMap<(isize, isize), isize>{
+        let mut cells = HashMap::new();
+        let size = chunk_size();
+        for (chunk_cord, chunk) in self.chunks.iter(){
+            let chunk = chunk.read().unwrap();
+            if chunk.number_of_elements() == 0{
+                continue;
+            }
+            for y in 0..size.1{
+                for x in 0..size.0{
+                    if let Some(strength) = chunk.get((x, y)).and_then(|e| e.get_type().material().properties().structural_strength){
+                        cells.insert(local_cord_to_global((x, y), *chunk_cord, self.chunk_size), strength);
+                    }
+                }
+            }
+        }
+        cells
+    }
+
+    // solids rest on the ground, anchors, powder or rigid bodies, or hang off a
+    // supported solid within their strength. climbing against gravity is free
+    pub(super) fn collapse_unsupported(&mut self){
+        let cells = self.structural_cells();
+        let world = self.world_rect();
+
+        let mut span: HashMap<(isize, isize), isize> = HashMap::new();
+        let mut queue = VecDeque::new();
+        for (cord, strength) in cells.iter(){
+            let gravity = self.gravity_at(*cord);
+            let below = self.edges.resolve(world, step(*cord, gravity.down(), 1));
+            // rigid bodies are the only solids outside of structures
+            let rests_on_loose = below.is_ok_and(|below| self.get(below).is_some_and(|e| e.movable_solid().is_some() ||
+                (e.solid().is_some() && e.get_type().material().properties().structural_strength.is_none())));
+            if *strength == isize::MAX || gravity.is_zero() || below.is_err() || rests_on_loose{
+                span.insert(*cord, 0);
+                queue.push_back(*cord);
+            }
+        }
+
+        while let Some(cord) = queue.pop_front(){
+            let current = span[&cord];
+            let down = self.gravity_at(cord).down();
+            for offset in [(0, -1), (-1, 0), (1, 0), (0, 1)]{
+                let next = (cord.0 + offset.0, cord.1 + offset.1);
+                let cost = if offset.0 * down.0 + offset.1 * down.1 < 0 {0} else {1};
+                let strength = match cells.get(&next) {
+                    Some(strength) => *strength,
+                    None => continue,
+                };
+                let next_span = current + cost;
+                if next_span > strength || span.get(&next).is_some_and(|s| *s <= next_span){
+                    continue;
+                }
+                span.insert(next, next_span);
+                if cost == 0{
+                    queue.push_front(next);
+                }
+                else{
+                    queue.push_back(next);
+                }
+            }
+        }
+
+        for cord in cells.keys(){
+            if !span.contains_key(cord){
+                self.set(*cord, Some(Element::debris()));
+            }
+        }
+    }
+}
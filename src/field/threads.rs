@@ -1,61 +1,110 @@
-use std::{sync::{Arc, Mutex, mpsc::{Receiver, Sender}, RwLock}, thread, collections::HashMap};
+use std::{time::{Duration, Instant}, sync::{Arc, Mutex, Condvar}};
+
+use rayon::{ThreadPool, ThreadPoolBuilder};
 
 use crate::elements::ElementData;
 
-use super::{chunk::Chunk, chunk_context::{ChunkContext, UnsolvedActions}, local_cord_to_global, ChunkCord, ChunkRef};
+use super::{chunk::UpdateStamp, gravity::GravityMap, wind::WindMap, chunk_context::{ChunkContext, ChunkNeighbourhood, UnsolvedActions}, local_cord_to_global, ChunkCord, ChunkRef};
 
 
+pub struct ChunkTask{
+    pub chunk: ChunkRef,
+    pub chunk_cord: ChunkCord,
+    pub neighbours: ChunkNeighbourhood,
+    pub gravity: Arc<GravityMap>,
+    pub wind: Arc<WindMap>,
+    pub stamp: UpdateStamp,
+}
 
-pub enum TaskMessage {
-    UpdateChunk(ChunkContext)
+pub struct ChunkResult{
+    pub unsolved: Vec<UnsolvedActions>, 
+    pub updated: Vec<(isize, isize)>,
+    pub chunk: ChunkCord,
+    pub cost: Duration,
+    pub processed: usize,
+    pub voided: usize,
 }
 
-pub enum ResultMessage {
-    UnsolvedActions{
-    unsolved: Vec<UnsolvedActions>, 
-    updated: Vec<(isize, isize)>
-    }
+pub struct WorkerPool{
+    pool: Option<ThreadPool>,
+    exited: Arc<(Mutex<usize>, Condvar)>,
 }
 
-pub fn spawn_field_worker_thread(task_reciver: Arc<Mutex<Receiver<TaskMessage>>>, result_sender: Sender<ResultMessage>, index: usize) -> thread::JoinHandle<()>{
-    thread::spawn(move ||{
-        let reciver = task_reciver;
-        let sender = result_sender;
-        loop {
-            let task = {reciver.lock().unwrap().recv().unwrap()};
-            match task{
-                TaskMessage::UpdateChunk(context) => {
-                    let (unsolved, updated) = update_chunk(context);
-                    sender.send(ResultMessage::UnsolvedActions{
-                        unsolved,
-                        updated,
-                    });
-                },
-            }
+impl WorkerPool {
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R{
+        self.pool.as_ref().expect("worker pool used after shutdown").install(op)
+    }
+
+    // dropping a rayon pool doesn't wait for its threads to exit
+    pub fn shutdown(&mut self){
+        let pool = match self.pool.take() {
+            Some(pool) => pool,
+            None => return,
+        };
+        let threads = pool.current_num_threads();
+        drop(pool);
+        let (exited, all_exited) = &*self.exited;
+        let mut exited = exited.lock().unwrap();
+        while *exited < threads{
+            exited = all_exited.wait(exited).unwrap();
         }
-    })
+    }
 }
 
-fn update_chunk(mut chunk_context: ChunkContext) -> (Vec<UnsolvedActions>, Vec<(isize, isize)>){
-    let update_rect = chunk_context.current_chunk().read().unwrap().get_update_rect();
+// zero lets rayon pick one per core
+pub fn build_worker_pool(number_of_threads: usize) -> Result<WorkerPool, String>{
+    let exited = Arc::new((Mutex::new(0), Condvar::new()));
+    let on_exit = exited.clone();
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(number_of_threads)
+        .thread_name(|i| format!("field worker {}", i))
+        .exit_handler(move |_| {
+            let (exited, all_exited) = &*on_exit;
+            *exited.lock().unwrap() += 1;
+            all_exited.notify_all();
+        })
+        .build()
+        .map_err(|e| format!("can't start the field workers: {}", e))?;
+    Ok(WorkerPool { pool: Some(pool), exited })
+}
+
+pub fn run_chunk_task(task: ChunkTask) -> ChunkResult{
+    let start = Instant::now();
+    let (unsolved, updated, processed, voided) = {
+        let mut chunk = task.chunk.write().unwrap();
+        update_chunk(ChunkContext::new(&mut chunk, task.chunk_cord, &task.neighbours, &task.gravity, &task.wind, task.stamp))
+    };
+    ChunkResult { unsolved, updated, chunk: task.chunk_cord, cost: start.elapsed(), processed, voided }
+}
+
+fn update_chunk(mut chunk_context: ChunkContext) -> (Vec<UnsolvedActions>, Vec<(isize, isize)>, usize, usize){
+    let update_rect = chunk_context.current_chunk().get_update_rect();
     let height = update_rect.bottom() - update_rect.top();
     let width = update_rect.right() - update_rect.left();
     let p = if chunk_context.parity() {1} else {0};
+    let mut processed = 0;
     for y in ((0..(height/2+(height * p)%2)).rev().map(|y| 1 - p + y*2)
                     .chain((0..height/2+(height * (1-p))%2).map(|y| y*2 + p)))
                     .map(|y| {y + update_rect.top()}).rev(){
+        if !chunk_context.current_chunk().row_is_dirty(y){
+            continue;
+        }
         for x in ((0..(width/2+(width * p)%2)).rev().map(|x| 1 - p + x*2)
                     .chain((0..width/2+(width * (1-p))%2).map(|x| x*2 + p)))
                     .map(|x| {x + update_rect.left()}){
             let local_cord = (x, y);
+            if !chunk_context.current_chunk().is_dirty(local_cord){
+                continue;
+            }
             let (element, should_update) =  {
-                let parity = chunk_context.current_chunk().read().unwrap().parity(local_cord);
-                let element = chunk_context.current_chunk().read().unwrap().get(local_cord);
-                (element, parity == chunk_context.parity())
+                let stamp = chunk_context.current_chunk().stamp(local_cord);
+                let element = chunk_context.current_chunk().get(local_cord);
+                (element, stamp != chunk_context.stamp())
             };
             if let Some(element) = element{
                 if should_update{
-                    element.update(local_cord_to_global(local_cord,chunk_context.current_chunk_cord()), 
+                    processed += 1;
+                    element.update(local_cord_to_global(local_cord,chunk_context.current_chunk_cord(), chunk_context.chunk_size()), 
                     &mut chunk_context);
                 }
                 else{
@@ -64,5 +113,5 @@ fn update_chunk(mut chunk_context: ChunkContext) -> (Vec<UnsolvedActions>, Vec<(
             }
         }
     }
-    (chunk_context.unsolved_actions, chunk_context.updated_coordinates)
+    (chunk_context.unsolved_actions, chunk_context.updated_coordinates, processed, chunk_context.voided)
 }
\ No newline at end of file
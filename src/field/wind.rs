@@ -0,0 +1,97 @@
+use rand::{thread_rng, Rng};
+
+use super::rect::Rect;
+
+pub const WIND_TILE: isize = 8;
+pub const MAX_WIND: f32 = 4.;
+const WIND_DECAY: f32 = 0.98;
+const WIND_MIN: f32 = 0.05;
+// scales wind speed over density into the chance of being pushed
+const WIND_PUSH: f32 = 2.;
+
+#[derive(Clone, Default)]
+pub struct WindMap{
+    size: (isize, isize),
+    tiles: Vec<(f32, f32)>,
+    calm: bool,
+}
+
+fn length(wind: (f32, f32)) -> f32{
+    (wind.0 * wind.0 + wind.1 * wind.1).sqrt()
+}
+
+impl WindMap {
+    pub fn new(world_size: (isize, isize)) -> WindMap{
+        let size = ((world_size.0 as usize).div_ceil(WIND_TILE as usize) as isize, (world_size.1 as usize).div_ceil(WIND_TILE as usize) as isize);
+        WindMap { size, tiles: vec![(0., 0.); (size.0 * size.1) as usize], calm: true }
+    }
+
+    fn index(&self, position: (isize, isize)) -> Option<usize>{
+        let tile = (position.0.div_euclid(WIND_TILE), position.1.div_euclid(WIND_TILE));
+        if tile.0 < 0 || tile.1 < 0 || tile.0 >= self.size.0 || tile.1 >= self.size.1{
+            return None;
+        }
+        Some((tile.1 * self.size.0 + tile.0) as usize)
+    }
+
+    pub fn is_calm(&self) -> bool{
+        self.calm
+    }
+
+    pub fn at(&self, position: (isize, isize)) -> (f32, f32){
+        self.index(position).map_or((0., 0.), |i| self.tiles[i])
+    }
+
+    pub fn blow(&mut self, position: (isize, isize), velocity: (f32, f32)){
+        if let Some(index) = self.index(position){
+            let tile = &mut self.tiles[index];
+            *tile = (tile.0 + velocity.0, tile.1 + velocity.1);
+            let speed = length(*tile);
+            if speed > MAX_WIND{
+                *tile = (tile.0 * MAX_WIND / speed, tile.1 * MAX_WIND / speed);
+            }
+            self.calm = false;
+        }
+    }
+
+    pub fn decay(&mut self){
+        if self.calm{
+            return;
+        }
+        let mut calm = true;
+        for tile in self.tiles.iter_mut(){
+            *tile = (tile.0 * WIND_DECAY, tile.1 * WIND_DECAY);
+            if length(*tile) < WIND_MIN{
+                *tile = (0., 0.);
+            }
+            else{
+                calm = false;
+            }
+        }
+        self.calm = calm;
+    }
+
+    pub fn gust(&self, position: (isize, isize), density: f64) -> Option<(isize, isize)>{
+        let wind = self.at(position);
+        let speed = length(wind);
+        if speed == 0.{
+            return None;
+        }
+        let chance = (speed * WIND_PUSH / density as f32).min(1.);
+        if !thread_rng().gen_bool(chance as f64){
+            return None;
+        }
+        Some(((wind.0 / speed).round() as isize, (wind.1 / speed).round() as isize))
+    }
+
+    pub fn tiles(&self) -> Vec<(Rect<isize>, (f32, f32))>{
+        let mut tiles = Vec::new();
+        for (index, wind) in self.tiles.iter().enumerate(){
+            if *wind != (0., 0.){
+                let tile = (index as isize % self.size.0 * WIND_TILE, index as isize / self.size.0 * WIND_TILE);
+                tiles.push((Rect::from(tile, (tile.0 + WIND_TILE, tile.1 + WIND_TILE)), *wind));
+            }
+        }
+        tiles
+    }
+}
@@ -0,0 +1,115 @@
+use crate::{draw::{set_pix, fill_rect, draw_rect}, field::{rect::Rect, gravity::Gravity}};
+
+const GLYPH_SIZE: (isize, isize) = (3, 5);
+const SWATCH_SIZE: isize = 6;
+const MARGIN: isize = 2;
+const LINE_HEIGHT: isize = GLYPH_SIZE.1 + 2;
+
+const BACKGROUND: [u8; 4] = [0x10, 0x10, 0x10, 0xff];
+const TEXT: [u8; 4] = [0xe0, 0xe0, 0xe0, 0xff];
+const HIGHLIGHT: [u8; 4] = [0xff, 0xff, 0xff, 0xff];
+
+pub struct HudStats{
+    pub brush_size: (usize, usize),
+    pub strength: isize,
+    pub brush_gravity: Gravity,
+    pub ticks_per_second: f32,
+    pub active_chunks: usize,
+    pub chunks: usize,
+    pub elements: usize,
+}
+
+// 3x5 glyphs, the highest bit is the leftmost pixel
+fn glyph(c: char) -> [u8; 5]{
+    match c.to_ascii_uppercase() {
+        '0' => [0b111, 0b101, 0b101, 0b101, 0b111],
+        '1' => [0b010, 0b110, 0b010, 0b010, 0b111],
+        '2' => [0b111, 0b001, 0b111, 0b100, 0b111],
+        '3' => [0b111, 0b001, 0b111, 0b001, 0b111],
+        '4' => [0b101, 0b101, 0b111, 0b001, 0b001],
+        '5' => [0b111, 0b100, 0b111, 0b001, 0b111],
+        '6' => [0b111, 0b100, 0b111, 0b101, 0b111],
+        '7' => [0b111, 0b001, 0b010, 0b010, 0b010],
+        '8' => [0b111, 0b101, 0b111, 0b101, 0b111],
+        '9' => [0b111, 0b101, 0b111, 0b001, 0b111],
+        'A' => [0b010, 0b101, 0b111, 0b101, 0b101],
+        'B' => [0b110, 0b101, 0b110, 0b101, 0b110],
+        'C' => [0b011, 0b100, 0b100, 0b100, 0b011],
+        'D' => [0b110, 0b101, 0b101, 0b101, 0b110],
+        'E' => [0b111, 0b100, 0b110, 0b100, 0b111],
+        'F' => [0b111, 0b100, 0b110, 0b100, 0b100],
+        'G' => [0b011, 0b100, 0b101, 0b101, 0b011],
+        'H' => [0b101, 0b101, 0b111, 0b101, 0b101],
+        'I' => [0b111, 0b010, 0b010, 0b010, 0b111],
+        'J' => [0b001, 0b001, 0b001, 0b101, 0b010],
+        'K' => [0b101, 0b101, 0b110, 0b101, 0b101],
+        'L' => [0b100, 0b100, 0b100, 0b100, 0b111],
+        'M' => [0b101, 0b111, 0b111, 0b101, 0b101],
+        'N' => [0b110, 0b101, 0b101, 0b101, 0b101],
+        'O' => [0b010, 0b101, 0b101, 0b101, 0b010],
+        'P' => [0b110, 0b101, 0b110, 0b100, 0b100],
+        'Q' => [0b010, 0b101, 0b101, 0b110, 0b011],
+        'R' => [0b110, 0b101, 0b110, 0b101, 0b101],
+        'S' => [0b011, 0b100, 0b010, 0b001, 0b110],
+        'T' => [0b111, 0b010, 0b010, 0b010, 0b010],
+        'U' => [0b101, 0b101, 0b101, 0b101, 0b111],
+        'V' => [0b101, 0b101, 0b101, 0b101, 0b010],
+        'W' => [0b101, 0b101, 0b111, 0b111, 0b101],
+        'X' => [0b101, 0b101, 0b010, 0b101, 0b101],
+        'Y' => [0b101, 0b101, 0b010, 0b010, 0b010],
+        'Z' => [0b111, 0b001, 0b010, 0b100, 0b111],
+        '.' => [0b000, 0b000, 0b000, 0b000, 0b010],
+        ':' => [0b000, 0b010, 0b000, 0b010, 0b000],
+        '/' => [0b001, 0b001, 0b010, 0b100, 0b100],
+        '-' => [0b000, 0b000, 0b111, 0b000, 0b000],
+        _ => [0; 5],
+    }
+}
+
+pub fn text_width(text: &str) -> isize{
+    text.chars().count() as isize * (GLYPH_SIZE.0 + 1) - 1
+}
+
+pub fn draw_text(frame: &mut [u8], size: (u32, u32), position: (isize, isize), text: &str, color: &[u8; 4]){
+    for (i, c) in text.chars().enumerate(){
+        let left = position.0 + i as isize * (GLYPH_SIZE.0 + 1);
+        for (y, row) in glyph(c).iter().enumerate(){
+            for x in 0..GLYPH_SIZE.0{
+                if row & (1 << (GLYPH_SIZE.0 - 1 - x)) != 0{
+                    set_pix(frame, size, (left + x, position.1 + y as isize), color);
+                }
+            }
+        }
+    }
+}
+
+pub fn draw_hud(frame: &mut [u8], size: (u32, u32), palette: &[[u8; 4]], selected: usize, stats: &HudStats){
+    let lines = [
+        format!("{}: SIZE {}X{} STR {}", selected, stats.brush_size.0, stats.brush_size.1, stats.strength),
+        format!("BRUSH GRAVITY {}", stats.brush_gravity.name()),
+        format!("TPS {:.0}", stats.ticks_per_second),
+        format!("CHUNKS {}/{}", stats.active_chunks, stats.chunks),
+        format!("CELLS {}", stats.elements),
+        "TAB NEXT BRUSH".to_string(),
+    ];
+
+    let palette_width = palette.len() as isize * (SWATCH_SIZE + 1) - 1;
+    let text_width = lines.iter().map(|l| text_width(l)).max().unwrap_or(0);
+    let width = palette_width.max(text_width) + MARGIN * 2;
+    let height = SWATCH_SIZE + MARGIN * 3 + lines.len() as isize * LINE_HEIGHT;
+    fill_rect(frame, size, Rect::from((0, 0), (width, height)), &BACKGROUND);
+
+    for (i, color) in palette.iter().enumerate(){
+        let left = MARGIN + i as isize * (SWATCH_SIZE + 1);
+        let swatch = Rect::from((left, MARGIN), (left + SWATCH_SIZE, MARGIN + SWATCH_SIZE));
+        fill_rect(frame, size, swatch, color);
+        if i == selected{
+            draw_rect(frame, size, Rect::from((left - 1, MARGIN - 1), (left + SWATCH_SIZE + 1, MARGIN + SWATCH_SIZE + 1)), &HIGHLIGHT);
+        }
+    }
+
+    for (i, line) in lines.iter().enumerate(){
+        let top = SWATCH_SIZE + MARGIN * 2 + i as isize * LINE_HEIGHT;
+        draw_text(frame, size, (MARGIN, top), line, &TEXT);
+    }
+}
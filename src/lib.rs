@@ -0,0 +1,4 @@
+pub mod elements;
+pub mod field;
+pub mod scenes;
+pub mod repose;
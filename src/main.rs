@@ -1,7 +1,13 @@
-mod elements;
-mod field;
 mod ubresenham;
+mod camera;
+mod config;
+mod draw;
+mod hud;
+mod overlays;
 
+use sandbox2::{elements, field, scenes, repose};
+
+use std::env;
 use std::error::Error;
 use std::sync::{Arc, Mutex, mpsc};
 use std::{thread, cmp};
@@ -10,9 +16,17 @@ use std::time::{Instant, Duration};
 use elements::movable_solids::MovableSolid;
 use elements::{Element};
 use field::Field;
-use field::chunk::CHUNK_SIZE;
+use field::metrics::{MetricsWriter, TickMetrics};
+use field::chunk::set_chunk_size;
 use field::rect::Rect;
+use field::rigid_body::RigidBodyKind;
+use field::gravity::Gravity;
 use ubresenham::Ubresenham;
+use camera::Camera;
+use config::Config;
+use draw::{convert_cords, draw_rect};
+use hud::{HudStats, draw_hud};
+use overlays::{DebugOverlays, Overlay};
 use winit::dpi::PhysicalSize;
 use winit::event::{Event, WindowEvent, VirtualKeyCode, MouseScrollDelta};
 use winit::window::WindowBuilder;
@@ -25,35 +39,148 @@ enum InputMessage{
     RClick(f32, f32),
     Scroll(isize),
     Number(usize),
+    Strength(isize),
     MousePosition(f32, f32),
+    Pan(f32, f32),
+    Zoom(isize),
+    Resize(u32, u32),
     FlipBoxes,
+    FlipStructuralMode,
+    FlipHud,
+    FlipOverlay(Overlay),
+    NextGravity,
+    NextBrush,
 }
-const CHUNK_NUMBER: (usize, usize) = (8, 8);
 
-const SCALE_FACTOR: u32 = 6;
+#[derive(Clone, Copy)]
+enum Brush{
+    Element(fn(isize) -> Element),
+    RigidBody(RigidBodyKind),
+    Gravity,
+    Fan,
+}
 
-const THREAD_NUMBER: usize = 16;
+impl Brush {
+    fn color(&self, strength: isize) -> [u8; 4]{
+        match self {
+            Brush::Element(element) => element(strength).get_color(),
+            Brush::RigidBody(kind) => kind.material().get_color(),
+            Brush::Gravity => GRAVITY_BRUSH_COLOR,
+            Brush::Fan => FAN_BRUSH_COLOR,
+        }
+    }
+}
+
+const TPS_SMOOTHING: f32 = 0.05;
+const MAX_STRENGTH: isize = 20;
+const GRAVITY_BRUSH_COLOR: [u8; 4] = [0x60, 0x60, 0xff, 0xff];
+const FAN_BRUSH_COLOR: [u8; 4] = [0x60, 0xff, 0xff, 0xff];
+// wind speed a fan stroke adds per point of strength
+const WIND_PER_STRENGTH: f32 = 0.2;
 
-const FPS: f32 = 300.;
+fn world_isize(config: &Config) -> (isize, isize){
+    let size = config.world_size();
+    (size.0 as isize, size.1 as isize)
+}
+
+fn run_repose(config: &Config, powder: &str, ticks: usize) -> Result<(), Box<dyn Error>>{
+    let element = repose::powder(powder)?;
+    if config.gravity != Gravity::Down{
+        return Err(format!("the repose test pours onto the floor and needs gravity down, not {}", config.gravity.name()).into());
+    }
+    let mut field = Field::new(config.chunk_number, config.thread_number)?;
+    field.set_edges(config.edges)?;
+    field.set_gravity(config.gravity);
+    let pile = repose::pour_and_measure(&mut field, world_isize(config), element, ticks)
+        .ok_or("the pile is too small to measure, pour for more ticks")?;
+    println!("{}: poured {} ticks, {} elements", powder, ticks, field.number_of_elements());
+    println!("pile height {} width {}", pile.height, pile.width);
+    println!("angle left {:.1} right {:.1} mean {:.1} (repose angle {:.1})",
+        pile.left, pile.right, pile.mean(), element.properties().repose_angle);
+    Ok(())
+}
+
+fn run_headless(config: &Config, ticks: usize) -> Result<(), Box<dyn Error>>{
+    let mut field = Field::new(config.chunk_number, config.thread_number)?;
+    field.set_edges(config.edges)?;
+    field.set_gravity(config.gravity);
+    scenes::load(&config.scene, &mut field, world_isize(config))?;
+    let mut writer = match &config.metrics {
+        Some(path) => Some(MetricsWriter::create(path)?),
+        None => None,
+    };
+
+    let mut sum = TickMetrics::default();
+    let mut slowest = Duration::ZERO;
+    for _ in 0..ticks{
+        field.update();
+        let metrics = field.last_tick_metrics();
+        if let Some(writer) = writer.as_mut(){
+            writer.write(metrics)?;
+        }
+        sum.total += metrics.total;
+        sum.dispatch += metrics.dispatch;
+        sum.workers += metrics.workers;
+        sum.unsolved_actions += metrics.unsolved_actions;
+        sum.remove_empty_chunks += metrics.remove_empty_chunks;
+        sum.cells_processed += metrics.cells_processed;
+        sum.cells_changed += metrics.cells_changed;
+        slowest = slowest.max(metrics.total);
+    }
+    if let Some(writer) = writer{
+        writer.finish()?;
+    }
+
+    let per_tick = |d: Duration| d.as_secs_f64() * 1e6 / cmp::max(1, ticks) as f64;
+    println!("scene {}: {} ticks in {:.3}s", config.scene, ticks, sum.total.as_secs_f64());
+    println!("tick avg {:.0}us max {}us", per_tick(sum.total), slowest.as_micros());
+    println!("dispatch {:.0}us workers {:.0}us unsolved {:.0}us remove empty {:.0}us",
+        per_tick(sum.dispatch), per_tick(sum.workers), per_tick(sum.unsolved_actions), per_tick(sum.remove_empty_chunks));
+    println!("cells processed {} changed {} voided {}, {} elements left",
+        sum.cells_processed, sum.cells_changed, field.voided_cells(), field.number_of_elements());
+    Ok(())
+}
 
 fn main() -> Result<(), Box<dyn Error>> {
 
+    let config = Config::from_args(env::args().skip(1))?;
+    if !set_chunk_size(config.chunk_size){
+        return Err(format!("chunk size {}x{} could not be set, it was already fixed", config.chunk_size.0, config.chunk_size.1).into());
+    }
+    if let Some(ticks) = config.headless{
+        if let Some(powder) = &config.repose{
+            return run_repose(&config, powder, ticks);
+        }
+        return run_headless(&config, ticks);
+    }
+    let scale_factor = config.scale_factor;
+
     let mut draw_boxes: bool = false;
+
+    let mut draw_hud_overlay: bool = true;
+    
+    let elements = [Brush::Element(|_|Element::wet_sand()), Brush::Element(|_|Element::sand()), Brush::Element(|_|Element::water()), 
+        Brush::Element(|_|Element::oil()), Brush::Element(|_|Element::block()), 
+        Brush::Element(Element::acid_with_strength), Brush::Element(|_|Element::glass()),
+        Brush::Element(|_|Element::metal()), Brush::Element(|_|Element::battery()), Brush::Element(|_|Element::lamp()), 
+        Brush::Element(|_|Element::heater()), Brush::Element(|_|Element::salt()), Brush::Element(|_|Element::salt_water()), 
+        Brush::Element(|_|Element::ice()), Brush::Element(|_|Element::alcohol()), Brush::Element(Element::base_with_strength),
+        Brush::RigidBody(RigidBodyKind::Crate), Brush::RigidBody(RigidBodyKind::Boulder), Brush::Element(|_|Element::anchor()), Brush::Gravity, Brush::Fan,
+        Brush::Element(|_|Element::honey()), Brush::Element(|_|Element::mercury()), Brush::Element(|_|Element::slime())];
     
-    let elements = [||Element::wet_sand(), ||Element::sand(), ||Element::water(), ||Element::oil(), ||Element::block(), ||Element::acid(), ||Element::glass()];
+    let mut strength: isize = 5;
     
     let mut element_index:usize = 1;
 
     let event_loop = EventLoop::new();
     let mut input = WinitInputHelper::new();
-    let pix_number = ((CHUNK_NUMBER.0 * CHUNK_SIZE.0) as u32, (CHUNK_NUMBER.1 * CHUNK_SIZE.1) as u32);
-    let max_inner_size: PhysicalSize<u32> = pix_number.into();
-    let window_size: PhysicalSize<u32> = (pix_number.0 * SCALE_FACTOR, pix_number.1 * SCALE_FACTOR).into();
+    let pix_number = config.world_size();
+    let window_size: PhysicalSize<u32> = (pix_number.0 * scale_factor, pix_number.1 * scale_factor).into();
 
     let window = WindowBuilder::new()
         .with_fullscreen(None)
         .with_inner_size(window_size)
-        .with_max_inner_size(max_inner_size)
+        .with_resizable(true)
         .with_title("wgpu first steps")
         .build(&event_loop)
         .unwrap();
@@ -67,19 +194,28 @@ fn main() -> Result<(), Box<dyn Error>> {
 
     let (sender, receiv) = mpsc::channel();
 
+    let mut field = Field::new(config.chunk_number, config.thread_number)?;
     thread::spawn(move || { 
         let mut prev_spawn_cord: Option<(usize, usize)> = None;
-        let mut field = Field::new(CHUNK_NUMBER, THREAD_NUMBER);
-        let mut previus_chunks = Vec::new();
-        let mut previus_rects = Vec::new();
+        field.set_edges(config.edges).unwrap_or_else(|e| eprintln!("{}", e));
+        field.set_gravity(config.gravity);
+        scenes::load(&config.scene, &mut field, world_isize(&config)).unwrap_or_else(|e| eprintln!("{}", e));
         let mut brush_size: (usize, usize) = (3,3);
-        
-        let mut mouse_prev: Option<(usize, usize)> = None;
-        let mut brush_size_prev: (usize, usize) = brush_size;
-        let mut prev_draw_boxes = draw_boxes;
+        let mut camera = Camera::new(pix_number, pix_number);
+        let mut world_frame: Vec<u8> = [0x00, 0x00, 0x00, 0xff].repeat((pix_number.0 * pix_number.1) as usize);
+        let mut mouse_position: Option<(usize, usize)> = None;
+        let mut debug_overlays = DebugOverlays::default();
+        let mut brush_gravity = Gravity::Up;
+        let mut ticks_per_second = config.fps;
+        let mut prev_loop_start = Instant::now();
         loop{
             
             let loop_start = Instant::now();
+            let tick_time = (loop_start - prev_loop_start).as_secs_f32();
+            if tick_time > 0. {
+                ticks_per_second += (1. / tick_time - ticks_per_second) * TPS_SMOOTHING;
+            }
+            prev_loop_start = loop_start;
             window.request_redraw();
             field.update();
             let mut inputs = Vec::new();
@@ -93,9 +229,13 @@ fn main() -> Result<(), Box<dyn Error>> {
 
                 let mut spawn_element = None;
 
+                let mut spawn_body = None;
+
+                let mut spawn_gravity = None;
+
+                let mut spawn_fan = false;
+
                 let mut spawn_cord = (0., 0.);
-                
-                let mut mouse_position = None;
 
                 
                 for input in inputs.into_iter(){
@@ -103,36 +243,92 @@ fn main() -> Result<(), Box<dyn Error>> {
                         InputMessage::Click(x, y) => {
                             spawn = true;
                             spawn_cord = (x, y);
-                            spawn_element = Some(elements[element_index]());
+                            match elements[element_index] {
+                                Brush::Element(element) => spawn_element = Some(element(strength)),
+                                Brush::RigidBody(kind) => spawn_body = Some(kind),
+                                Brush::Gravity => spawn_gravity = Some(Some(brush_gravity)),
+                                Brush::Fan => spawn_fan = true,
+                            }
                         },
                         InputMessage::Number(index) => {
                             element_index = index % elements.len();
                         },
+                        InputMessage::Strength(v) => {
+                            strength = (strength + v).clamp(1, MAX_STRENGTH);
+                        },
                         InputMessage::RClick(x, y) => {
                             spawn = true;
                             spawn_cord = (x, y);
                             spawn_element = None;
+                            if let Brush::Gravity = elements[element_index]{
+                                spawn_gravity = Some(None);
+                            }
                         },
                         InputMessage::Scroll(v) => {
                             brush_size = (cmp::min(10, cmp::max(1, brush_size.0 as isize + v)) as usize, 
                             cmp::min(10, cmp::max(1, brush_size.1 as isize + v)) as usize)
                         },
                         InputMessage::MousePosition(x, y) => {
-                            if let Ok(pos) = pix.window_pos_to_pixel((x, y)){
-                                mouse_position = Some(pos);
+                            mouse_position = pix.window_pos_to_pixel((x, y)).ok();
+                        },
+                        InputMessage::Pan(dx, dy) => {
+                            camera.pan((dx, dy));
+                        },
+                        InputMessage::Resize(width, height) => {
+                            let view_size = (cmp::max(1, width / scale_factor), cmp::max(1, height / scale_factor));
+                            pix.resize_buffer(view_size.0, view_size.1);
+                            camera.resize(view_size);
+                        },
+                        InputMessage::Zoom(v) => {
+                            if let Some(mouse_pos) = mouse_position{
+                                camera.zoom_at(v, mouse_pos);
                             }
                         },
                         InputMessage::FlipBoxes => {
                              draw_boxes = !draw_boxes;
+                        },
+                        InputMessage::FlipStructuralMode => {
+                            field.set_structural_mode(!field.structural_mode());
+                        },
+                        InputMessage::FlipHud => {
+                            draw_hud_overlay = !draw_hud_overlay;
+                        },
+                        InputMessage::FlipOverlay(overlay) => {
+                            debug_overlays.flip(overlay);
+                        },
+                        InputMessage::NextGravity => {
+                            brush_gravity = brush_gravity.next();
+                        },
+                        InputMessage::NextBrush => {
+                            element_index = (element_index + 1) % elements.len();
                         }
                     }
                 }
 
                 if spawn {
-                    let mut spawn_func = |cord: (usize, usize)| 
-                    {field.set_in_area((cord.0 as isize, cord.1 as isize), brush_size, spawn_element)};
-                    if let Ok(cord) = pix.window_pos_to_pixel(spawn_cord){
-                        if let Some(prev_cord) = prev_spawn_cord{
+                    let mut spawn_func = |cord: (usize, usize)| {
+                        match spawn_gravity {
+                            Some(gravity) => field.paint_gravity((cord.0 as isize, cord.1 as isize), brush_size, gravity),
+                            None => field.set_in_area((cord.0 as isize, cord.1 as isize), brush_size, spawn_element),
+                        }
+                    };
+                    if let Some(cord) = pix.window_pos_to_pixel(spawn_cord).ok().and_then(|p| camera.view_to_world(p)){
+                        if let Some(kind) = spawn_body{
+                            if prev_spawn_cord.is_none(){
+                                field.spawn_rigid_body((cord.0 as isize, cord.1 as isize), brush_size, kind);
+                            }
+                        }
+                        else if spawn_fan{
+                            if let Some(prev_cord) = prev_spawn_cord{
+                                let delta = (cord.0 as f32 - prev_cord.0 as f32, cord.1 as f32 - prev_cord.1 as f32);
+                                let length = (delta.0 * delta.0 + delta.1 * delta.1).sqrt();
+                                if length > 0.{
+                                    let speed = strength as f32 * WIND_PER_STRENGTH / length;
+                                    field.blow((cord.0 as isize, cord.1 as isize), brush_size, (delta.0 * speed, delta.1 * speed));
+                                }
+                            }
+                        }
+                        else if let Some(prev_cord) = prev_spawn_cord{
 
                             if cord == prev_cord{
                                 spawn_func(cord);
@@ -154,59 +350,46 @@ fn main() -> Result<(), Box<dyn Error>> {
                     prev_spawn_cord = None;
                 }
 
-                let frame = pix.get_frame();
+                update_frame(&mut world_frame, pix_number, &mut field);
 
-                let color_func = |p| { match field.get(p){
-                    Some(e) => e.get_color(),
-                    None => [0x00,0x00,0x00,0xff],
-                }};
+                let frame = pix.get_frame();
+                let view_size = camera.view_size();
 
-                if let Some(mouse_prev) = mouse_prev{
-                    draw_adaptive_color_rect(frame, 
-                        Rect::from_center((mouse_prev.0 as isize, mouse_prev.1 as isize), brush_size_prev), 
-                        color_func);
-                }
+                camera.blit(&world_frame, frame);
 
-                if prev_draw_boxes{
-                    let color_func = |p| { match field.get(p){
-                        Some(e) => e.get_color(),
-                        None => [0x00,0x00,0x00,0xff],
-                    }};
-                    for b in previus_chunks.iter(){
-                        draw_adaptive_color_rect(frame, *b, color_func);
-                    }
-                    for b in previus_rects.iter(){
-                        draw_adaptive_color_rect(frame, *b, color_func);
-                    }
-                }
-                
-                update_frame(frame, &mut field);
+                debug_overlays.draw(frame, view_size, &camera, &field);
 
                 if draw_boxes{
-                    previus_chunks.clear();
-                    previus_rects.clear();
                     for b in field.get_chunks(){
-                        draw_rect(frame, b, &[0xff, 0x00, 0x00, 0xff]);
-                        previus_chunks.push(b);
+                        draw_rect(frame, view_size, camera.rect_to_view(b), &[0xff, 0x00, 0x00, 0xff]);
                     }
                     for b in field.get_chunks_update_rects(){
-                        draw_rect(frame, b, &[0x00, 0xff, 0x00, 0xff]);
-                        previus_rects.push(b);
+                        draw_rect(frame, view_size, camera.rect_to_view(b), &[0x00, 0xff, 0x00, 0xff]);
                     }
                 }
 
-                if let Some(mouse_pos) = mouse_position{
-                    
-                    draw_rect(frame, Rect::from_center((mouse_pos.0 as isize, mouse_pos.1 as isize), brush_size), &[0xff, 0xff, 0xff, 0xff]);
+                if let Some(mouse_pos) = mouse_position.and_then(|p| camera.view_to_world(p)){
+                    let brush = Rect::from_center((mouse_pos.0 as isize, mouse_pos.1 as isize), brush_size);
+                    draw_rect(frame, view_size, camera.rect_to_view(brush), &[0xff, 0xff, 0xff, 0xff]);
                 }
 
-                mouse_prev = mouse_position;
-                brush_size_prev = brush_size;
-                prev_draw_boxes = draw_boxes;
+                if draw_hud_overlay{
+                    let palette: Vec<[u8; 4]> = elements.iter().map(|b| b.color(strength)).collect();
+                    let stats = HudStats{
+                        brush_size,
+                        strength,
+                        brush_gravity,
+                        ticks_per_second,
+                        active_chunks: field.active_chunk_count(),
+                        chunks: field.chunk_count(),
+                        elements: field.number_of_elements(),
+                    };
+                    draw_hud(frame, view_size, &palette, element_index, &stats);
+                }
 
             }
             let duration_sec = (Instant::now() - loop_start).as_secs_f32();
-            let wait_time = (1. / FPS) - duration_sec;
+            let wait_time = (1. / config.fps) - duration_sec;
             if wait_time > 0. {
                 thread::sleep(Duration::from_secs_f32(wait_time));
             }
@@ -231,6 +414,13 @@ fn main() -> Result<(), Box<dyn Error>> {
             } => {
                 *control_flow = ControlFlow::Exit;
             }
+            Event::WindowEvent {
+                event: WindowEvent::Resized(size),
+                ..
+            } if size.width > 0 && size.height > 0 => {
+                pix_for_draw.lock().unwrap().resize_surface(size.width, size.height);
+                sender.send(InputMessage::Resize(size.width, size.height)).ok();
+            }
             Event::WindowEvent { 
                 event: WindowEvent::MouseWheel { 
                      delta: MouseScrollDelta::LineDelta(_, delta) ,
@@ -241,7 +431,12 @@ fn main() -> Result<(), Box<dyn Error>> {
                 let val = delta.signum() as isize;
                 if *can_send && val != 0 {
                     *can_send = false;
-                    sender.send(InputMessage::Scroll(delta.signum() as isize)).ok();
+                    if input.held_control(){
+                        sender.send(InputMessage::Zoom(val)).ok();
+                    }
+                    else{
+                        sender.send(InputMessage::Scroll(val)).ok();
+                    }
                 }
             }
             
@@ -249,6 +444,12 @@ fn main() -> Result<(), Box<dyn Error>> {
         }
 
         if input.update(&event) {
+            if input.mouse_held(2) {
+                let (dx, dy) = input.mouse_diff();
+                if dx != 0. || dy != 0. {
+                    sender.send(InputMessage::Pan(dx / scale_factor as f32, dy / scale_factor as f32)).ok();
+                }
+            }
             if can_send_mouse {
                 if let Some(p) = input.mouse(){
                     can_send_mouse = false;
@@ -281,32 +482,68 @@ fn main() -> Result<(), Box<dyn Error>> {
                     ].into_iter().enumerate(){
                     if input.key_pressed(code){
                         *can_send = false;
-                        sender.send(InputMessage::Number(key_index)).ok();
+                        let page = if input.held_shift() {10} else {0};
+                        sender.send(InputMessage::Number(key_index + page)).ok();
                     }
                 }
 
+                if input.key_pressed(VirtualKeyCode::RBracket){
+                    *can_send = false;
+                    sender.send(InputMessage::Strength(1)).ok();
+                }
+                else if input.key_pressed(VirtualKeyCode::LBracket){
+                    *can_send = false;
+                    sender.send(InputMessage::Strength(-1)).ok();
+                }
+
                 if input.key_pressed(VirtualKeyCode::B){
                     *can_send = false;
                         sender.send(InputMessage::FlipBoxes).ok();
                 }
+
+                if input.key_pressed(VirtualKeyCode::I){
+                    *can_send = false;
+                    sender.send(InputMessage::FlipStructuralMode).ok();
+                }
+
+                if input.key_pressed(VirtualKeyCode::H){
+                    *can_send = false;
+                    sender.send(InputMessage::FlipHud).ok();
+                }
+
+                if input.key_pressed(VirtualKeyCode::G){
+                    *can_send = false;
+                    sender.send(InputMessage::NextGravity).ok();
+                }
+
+                if input.key_pressed(VirtualKeyCode::Tab){
+                    *can_send = false;
+                    sender.send(InputMessage::NextBrush).ok();
+                }
+
+                for (code, overlay) in [
+                    (VirtualKeyCode::F1, Overlay::Stamps),
+                    (VirtualKeyCode::F2, Overlay::StableTime),
+                    (VirtualKeyCode::F3, Overlay::KeptAlive),
+                    (VirtualKeyCode::F4, Overlay::Updated),
+                    (VirtualKeyCode::F5, Overlay::ChunkCost),
+                    (VirtualKeyCode::F6, Overlay::Gravity),
+                    (VirtualKeyCode::F7, Overlay::Wind),
+                    ]{
+                    if input.key_pressed(code){
+                        *can_send = false;
+                        sender.send(InputMessage::FlipOverlay(overlay)).ok();
+                    }
+                }
             }
         }
     });
     Ok(())
 }
 
-fn convert_cords(cord: (isize, isize)) -> Option<usize>{
-    if cord.0 < 0 || cord.1 < 0 || cord.0 >= (CHUNK_NUMBER.0 * CHUNK_SIZE.0) as isize || 
-    cord.1 >= (CHUNK_NUMBER.1 * CHUNK_SIZE.1) as isize{
-        return None;
-    }
-    let cord = (cord.0 as usize, cord.1 as usize);
-    Some(cord.1 * CHUNK_NUMBER.0 * CHUNK_SIZE.0 + cord.0)
-}
-
-fn update_frame(frame: &mut [u8], field: &mut Field){
+fn update_frame(frame: &mut [u8], size: (u32, u32), field: &mut Field){
     for (pix_cord, color) in field.load_pixels(){
-        if let Some(index) = convert_cords(pix_cord){
+        if let Some(index) = convert_cords(pix_cord, size){
             let pixel = &mut frame[index*4..(index+1)*4];
             pixel[0] = color[0];
             pixel[1] = color[1];
@@ -315,39 +552,3 @@ fn update_frame(frame: &mut [u8], field: &mut Field){
         }
     }
 }
-
-fn set_pix(frame: &mut [u8], pix: (isize, isize), color: &[u8; 4]){
-    let index = convert_cords(pix);
-    if index.is_none(){
-        return;
-    }
-    let index = index.unwrap();
-    let pixel_buf = &mut frame[index*4..(index+1)*4];
-
-    pixel_buf[0] = color[0];
-    pixel_buf[1] = color[1];
-    pixel_buf[2] = color[2];
-    pixel_buf[3] = color[3];
-}
-
-fn draw_adaptive_color_rect(frame: &mut [u8], rect: Rect<isize>, color_func: impl Fn((isize, isize)) -> [u8; 4]){
-    for x in rect.left()..rect.right(){
-        set_pix(frame, (x, rect.top()), &color_func((x, rect.top())));
-        set_pix(frame, (x, rect.bottom()-1), &color_func((x, rect.bottom()-1)));
-    }
-    for y in rect.top()..rect.bottom(){
-        set_pix(frame, (rect.left(), y), &color_func((rect.left(), y)));
-        set_pix(frame, (rect.right() - 1, y), &color_func((rect.right() - 1, y)));
-    }
-}
-
-fn draw_rect(frame: &mut [u8], rect: Rect<isize>, color: &[u8; 4]){
-    for x in rect.left()..rect.right(){
-        set_pix(frame, (x, rect.top()), color);
-        set_pix(frame, (x, rect.bottom()-1), color);
-    }
-    for y in rect.top()..rect.bottom(){
-        set_pix(frame, (rect.left(), y), color);
-        set_pix(frame, (rect.right() - 1, y), color);
-    }
-}
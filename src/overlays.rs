@@ -0,0 +1,116 @@
+use std::time::Duration;
+
+use crate::{camera::Camera, draw::{blend_rect, draw_rect}, field::{Field, rect::Rect, gravity::step}, hud::draw_text};
+
+const ALPHA: f32 = 0.6;
+const STABLE_TIME_SCALE: f32 = 100.;
+
+#[derive(Clone, Copy)]
+pub enum Overlay{
+    Stamps,
+    StableTime,
+    KeptAlive,
+    Updated,
+    ChunkCost,
+    Gravity,
+    Wind,
+}
+
+#[derive(Default)]
+pub struct DebugOverlays{
+    stamps: bool,
+    stable_time: bool,
+    kept_alive: bool,
+    updated: bool,
+    chunk_cost: bool,
+    gravity: bool,
+    wind: bool,
+}
+
+impl DebugOverlays {
+    pub fn flip(&mut self, overlay: Overlay){
+        let flag = match overlay {
+            Overlay::Stamps => &mut self.stamps,
+            Overlay::StableTime => &mut self.stable_time,
+            Overlay::KeptAlive => &mut self.kept_alive,
+            Overlay::Updated => &mut self.updated,
+            Overlay::ChunkCost => &mut self.chunk_cost,
+            Overlay::Gravity => &mut self.gravity,
+            Overlay::Wind => &mut self.wind,
+        };
+        *flag = !*flag;
+    }
+
+    fn per_cell_color(&self, field: &Field, position: (isize, isize)) -> Option<[u8; 4]>{
+        let element = field.get(position);
+        if self.kept_alive && field.kept_alive_at(position){
+            return Some([0xff, 0xff, 0x00, 0xff]);
+        }
+        if self.stable_time{
+            if let Some(stable_time) = element.and_then(|e| e.stable_time()){
+                let t = f32::min(stable_time as f32 / STABLE_TIME_SCALE, 1.);
+                return Some([(0xff as f32 * t) as u8, 0x00, (0xff as f32 * (1. - t)) as u8, 0xff]);
+            }
+        }
+        if self.stamps && element.is_some(){
+            return match field.stamp_at(position) {
+                Some(s) if s == field.last_stamp() => Some([0x00, 0xff, 0x00, 0xff]),
+                Some(_) => Some([0xff, 0x00, 0xff, 0xff]),
+                None => None,
+            };
+        }
+        None
+    }
+
+    pub fn draw(&self, frame: &mut [u8], view_size: (u32, u32), camera: &Camera, field: &Field){
+        let cell_rect = |p: (isize, isize)| camera.rect_to_view(Rect::from(p, (p.0 + 1, p.1 + 1)));
+
+        if self.stamps || self.stable_time || self.kept_alive{
+            for position in camera.visible_rect().into_iter(){
+                if let Some(color) = self.per_cell_color(field, position){
+                    blend_rect(frame, view_size, cell_rect(position), &color, ALPHA);
+                }
+            }
+        }
+
+        if self.updated{
+            for position in field.updated_this_tick(){
+                blend_rect(frame, view_size, cell_rect(*position), &[0xff, 0xff, 0xff, 0xff], ALPHA);
+            }
+        }
+
+        if self.gravity{
+            for (zone, gravity) in field.gravity_zones(){
+                let color = if gravity.is_zero() {[0x80, 0x80, 0x80, 0xff]} else {[0x40, 0x40, 0xff, 0xff]};
+                blend_rect(frame, view_size, camera.rect_to_view(zone), &color, ALPHA / 2.);
+                let center = ((zone.left() + zone.right()) / 2, (zone.top() + zone.bottom()) / 2);
+                for distance in 0..3{
+                    blend_rect(frame, view_size, cell_rect(step(center, gravity.down(), distance)), &[0xff, 0xff, 0xff, 0xff], ALPHA);
+                }
+            }
+        }
+
+        if self.wind{
+            for (tile, wind) in field.wind_tiles(){
+                let speed = (wind.0 * wind.0 + wind.1 * wind.1).sqrt();
+                let direction = ((wind.0 / speed).round() as isize, (wind.1 / speed).round() as isize);
+                let center = ((tile.left() + tile.right()) / 2, (tile.top() + tile.bottom()) / 2);
+                for distance in 0..=speed.ceil() as isize{
+                    blend_rect(frame, view_size, cell_rect(step(center, direction, distance)), &[0x00, 0xff, 0xff, 0xff], ALPHA);
+                }
+            }
+        }
+
+        if self.chunk_cost{
+            let costs = field.chunk_update_costs();
+            let max = costs.iter().map(|(_, c)| *c).max().unwrap_or(Duration::ZERO);
+            for (rect, cost) in costs{
+                let t = if max.is_zero() {0.} else {cost.as_secs_f32() / max.as_secs_f32()};
+                let color = [0xff, (0xff as f32 * (1. - t)) as u8, 0x00, 0xff];
+                let rect = camera.rect_to_view(rect);
+                draw_rect(frame, view_size, rect, &color);
+                draw_text(frame, view_size, (rect.left() + 2, rect.top() + 2), &cost.as_micros().to_string(), &color);
+            }
+        }
+    }
+}
@@ -0,0 +1,119 @@
+use crate::{elements::Element, field::Field};
+
+pub type PowderFactory = fn() -> Element;
+
+pub const POWDERS: [(&str, PowderFactory); 4] = [("sand", Element::sand), ("wet-sand", Element::wet_sand),
+    ("salt", Element::salt), ("debris", Element::debris)];
+
+// the rounded top and the toe of a pile are left out of the fit
+const FLANK_LOW: f64 = 0.2;
+const FLANK_HIGH: f64 = 0.8;
+const SETTLE_LIMIT: usize = 5000;
+// a solid stream piles up in the air and sprays off to the sides
+const POUR_PERIOD: usize = 4;
+
+pub struct PileAngle{
+    pub left: f64,
+    pub right: f64,
+    pub height: isize,
+    pub width: isize,
+}
+
+impl PileAngle {
+    pub fn mean(&self) -> f64{
+        (self.left + self.right) / 2.
+    }
+}
+
+pub fn powder(name: &str) -> Result<Element, String>{
+    POWDERS.iter().find(|(powder, _)| *powder == name.trim()).map(|(_, element)| element())
+        .ok_or(format!("unknown powder '{}', expected one of {}", name,
+            POWDERS.iter().map(|(powder, _)| *powder).collect::<Vec<_>>().join(", ")))
+}
+
+// assumes gravity pulls down
+pub fn pour_and_measure(field: &mut Field, size: (isize, isize), element: Element, ticks: usize) -> Option<PileAngle>{
+    let spout = (size.0 / 2, 1);
+    for tick in 0..ticks{
+        if tick % POUR_PERIOD == 0 && field.get(spout).is_none(){
+            field.set(spout, Some(element));
+        }
+        field.update();
+    }
+    for _ in 0..SETTLE_LIMIT{
+        if field.active_chunk_count() == 0{
+            break;
+        }
+        field.update();
+    }
+    measure(field, size)
+}
+
+pub fn measure(field: &Field, size: (isize, isize)) -> Option<PileAngle>{
+    let heights: Vec<isize> = (0..size.0)
+        .map(|x| (0..size.1).find(|y| field.get((x, *y)).is_some()).map_or(0, |top| size.1 - top))
+        .collect();
+    let (peak, height) = heights.iter().copied().enumerate().max_by_key(|(_, h)| *h)?;
+    if height < 2{
+        return None;
+    }
+    let flank = |columns: &mut dyn Iterator<Item = usize>| {
+        let points: Vec<(f64, f64)> = columns
+            .map(|x| (x as f64, heights[x] as f64))
+            .filter(|(_, h)| *h >= height as f64 * FLANK_LOW && *h <= height as f64 * FLANK_HIGH)
+            .collect();
+        slope(&points).map(|s| s.abs().atan().to_degrees())
+    };
+    let left = flank(&mut (0..peak))?;
+    let right = flank(&mut (peak + 1..heights.len()))?;
+    let width = heights.iter().filter(|h| **h > 0).count() as isize;
+    Some(PileAngle { left, right, height, width })
+}
+
+// least squares
+fn slope(points: &[(f64, f64)]) -> Option<f64>{
+    if points.len() < 2{
+        return None;
+    }
+    let n = points.len() as f64;
+    let mean_x = points.iter().map(|p| p.0).sum::<f64>() / n;
+    let mean_y = points.iter().map(|p| p.1).sum::<f64>() / n;
+    let covariance: f64 = points.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    let variance: f64 = points.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    Some(covariance / variance)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIZE: (isize, isize) = (128, 64);
+
+    fn pile(rise: isize, run: isize, height: isize) -> Field{
+        let mut field = Field::new((4, 2), 1).unwrap();
+        for x in 0..SIZE.0{
+            let column = (height - (x - SIZE.0 / 2).abs() * rise / run).max(0);
+            for y in SIZE.1 - column..SIZE.1{
+                field.set((x, y), Some(Element::sand()));
+            }
+        }
+        field
+    }
+
+    #[test]
+    fn flanks_of_a_symmetric_pile_are_measured(){
+        let angle = measure(&pile(1, 1, 30), SIZE).unwrap();
+        assert_eq!(angle.height, 30);
+        assert!((angle.left - 45.).abs() < 1. && (angle.right - 45.).abs() < 1., "{} {}", angle.left, angle.right);
+
+        let angle = measure(&pile(1, 2, 30), SIZE).unwrap();
+        let expected = 0.5f64.atan().to_degrees();
+        assert!((angle.mean() - expected).abs() < 1., "{}", angle.mean());
+    }
+
+    #[test]
+    fn flat_or_empty_ground_has_no_angle(){
+        assert!(measure(&pile(1, 1, 1), SIZE).is_none());
+        assert!(measure(&Field::new((4, 2), 1).unwrap(), SIZE).is_none());
+    }
+}
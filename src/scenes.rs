@@ -0,0 +1,73 @@
+use crate::{elements::Element, field::{Field, chunk::chunk_size}};
+
+pub const SCENES: [&str; 7] = ["empty", "sand-collapse", "water-basin", "oil-water", "acid-block", "scattered", "communicating-vessels"];
+
+fn sand_collapse(field: &mut Field, size: (isize, isize)){
+    let width = size.0 / 2;
+    field.set_in_area((size.0 / 2, size.1 / 4), (width as usize, (size.1 / 2) as usize), Some(Element::sand()));
+}
+
+fn water_basin(field: &mut Field, size: (isize, isize)){
+    let wall = (2, (size.1 / 2) as usize);
+    field.set_in_area((size.0 / 8, size.1 * 3 / 4), wall, Some(Element::block()));
+    field.set_in_area((size.0 * 7 / 8, size.1 * 3 / 4), wall, Some(Element::block()));
+    field.set_in_area((size.0 / 2, size.1 / 4), ((size.0 / 2) as usize, (size.1 / 4) as usize), Some(Element::water()));
+}
+
+fn oil_water(field: &mut Field, size: (isize, isize)){
+    let layer = ((size.0 / 2) as usize, (size.1 / 8) as usize);
+    for i in 0..4{
+        let element = if i % 2 == 0 {Element::water()} else {Element::oil()};
+        field.set_in_area((size.0 / 2, size.1 / 2 + i * size.1 / 8), layer, Some(element));
+    }
+}
+
+fn acid_block(field: &mut Field, size: (isize, isize)){
+    field.set_in_area((size.0 / 2, size.1 * 3 / 4), ((size.0 / 2) as usize, (size.1 / 2) as usize), Some(Element::block()));
+    field.set_in_area((size.0 / 2, size.1 / 8), ((size.0 / 4) as usize, (size.1 / 4) as usize), Some(Element::acid()));
+}
+
+// mostly static chunks, only the batteries stay active
+fn scattered(field: &mut Field, size: (isize, isize)){
+    let chunk = chunk_size();
+    let chunk = (chunk.0 as isize, chunk.1 as isize);
+    field.set_in_area((size.0 / 2, size.1 / 2), (size.0 as usize, size.1 as usize), Some(Element::block()));
+    for y in (0..size.1).step_by(chunk.1 as usize){
+        for x in (0..size.0).step_by(chunk.0 as usize){
+            field.set((x + 1, y + 1), Some(Element::battery()));
+            field.set((x + chunk.0 - 2, y + chunk.1 - 2), Some(Element::battery()));
+        }
+    }
+}
+
+// water in the left arm only
+fn communicating_vessels(field: &mut Field, size: (isize, isize)){
+    let (left, right, top, bottom) = (size.0 / 4, size.0 * 3 / 4, size.1 / 4, size.1 * 7 / 8);
+    let arm = 8;
+    let inside = |x: isize, y: isize| y >= top && y < bottom && x >= left && x < right &&
+        (x < left + arm || x >= right - arm || y >= bottom - arm);
+    for y in top..=bottom{
+        for x in left - 1..=right{
+            if !inside(x, y){
+                field.set((x, y), Some(Element::block()));
+            }
+            else if (x < left + arm && y >= top + arm) || y >= bottom - arm{
+                field.set((x, y), Some(Element::water()));
+            }
+        }
+    }
+}
+
+pub fn load(name: &str, field: &mut Field, size: (isize, isize)) -> Result<(), String>{
+    match name {
+        "empty" => {},
+        "sand-collapse" => sand_collapse(field, size),
+        "water-basin" => water_basin(field, size),
+        "oil-water" => oil_water(field, size),
+        "acid-block" => acid_block(field, size),
+        "scattered" => scattered(field, size),
+        "communicating-vessels" => communicating_vessels(field, size),
+        _ => return Err(format!("unknown scene '{}', expected one of {}", name, SCENES.join(", "))),
+    }
+    Ok(())
+}